@@ -0,0 +1,3 @@
+-- ### EXPORTS
+drop trigger if exists set_timestamp on exports;
+drop table if exists exports;
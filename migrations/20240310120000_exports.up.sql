@@ -0,0 +1,18 @@
+-- ### EXPORTS
+create table if not exists exports
+(
+    id             uuid primary key     default gen_random_uuid(),
+    user_id        uuid        not null references users (id) on delete cascade,
+    status         varchar     not null default 'pending',
+    object_id      uuid,
+    download_token uuid        not null default gen_random_uuid(),
+    expires_at     timestamptz,
+    created_at     timestamptz not null default now(),
+    updated_at     timestamptz not null default now()
+);
+
+create trigger set_timestamp
+    before update
+    on exports
+    for each row
+execute procedure trigger_set_timestamp();
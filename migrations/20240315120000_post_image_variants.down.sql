@@ -0,0 +1,4 @@
+-- ### POSTS
+alter table posts
+    drop column if exists image_widths,
+    drop column if exists image_width;
@@ -0,0 +1,4 @@
+-- ### POSTS
+alter table posts
+    add column image_width  integer   not null default 0,
+    add column image_widths integer[] not null default '{}';
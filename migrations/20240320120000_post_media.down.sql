@@ -0,0 +1,3 @@
+-- ### POST MEDIA
+drop trigger if exists set_timestamp on post_media;
+drop table if exists post_media;
@@ -0,0 +1,23 @@
+-- ### POST MEDIA
+create table if not exists post_media
+(
+    id           uuid primary key     default gen_random_uuid(),
+    post_id      uuid        not null references posts (id) on delete cascade,
+    object_id    uuid        not null,
+    content_type varchar     not null,
+    width        integer     not null default 0,
+    widths       integer[]   not null default '{}',
+    position     integer     not null default 0,
+    alt_text     varchar     not null default '',
+    caption      text        not null default '',
+    created_at   timestamptz not null default now(),
+    updated_at   timestamptz not null default now()
+);
+
+create index if not exists post_media_post_id_position_idx on post_media (post_id, position);
+
+create trigger set_timestamp
+    before update
+    on post_media
+    for each row
+execute procedure trigger_set_timestamp();
@@ -0,0 +1,3 @@
+-- ### PENDING DELETIONS
+drop trigger if exists set_timestamp on pending_deletions;
+drop table if exists pending_deletions;
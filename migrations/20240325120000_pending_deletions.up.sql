@@ -0,0 +1,19 @@
+-- ### PENDING DELETIONS
+create table if not exists pending_deletions
+(
+    id              uuid primary key     default gen_random_uuid(),
+    key             varchar     not null,
+    attempts        integer     not null default 0,
+    last_error      text,
+    next_attempt_at timestamptz not null default now(),
+    created_at      timestamptz not null default now(),
+    updated_at      timestamptz not null default now()
+);
+
+create index if not exists pending_deletions_next_attempt_at_idx on pending_deletions (next_attempt_at);
+
+create trigger set_timestamp
+    before update
+    on pending_deletions
+    for each row
+execute procedure trigger_set_timestamp();
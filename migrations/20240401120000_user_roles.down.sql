@@ -0,0 +1,3 @@
+-- ### USERS
+alter table users
+    drop column if exists role;
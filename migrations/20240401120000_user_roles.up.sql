@@ -0,0 +1,3 @@
+-- ### USERS
+alter table users
+    add column role varchar not null default 'user' check (role in ('user', 'admin'));
@@ -0,0 +1,3 @@
+-- ### POSTS
+alter table posts
+    drop column if exists image_content_type;
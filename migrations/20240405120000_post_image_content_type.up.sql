@@ -0,0 +1,3 @@
+-- ### POSTS
+alter table posts
+    add column image_content_type varchar not null default '';
@@ -0,0 +1,2 @@
+-- ### IMAGE UPLOADS
+drop table if exists image_uploads;
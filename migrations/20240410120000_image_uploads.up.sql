@@ -0,0 +1,11 @@
+-- ### IMAGE UPLOADS
+create table if not exists image_uploads
+(
+    id         uuid primary key     default gen_random_uuid(),
+    post_id    uuid        not null references posts (id) on delete cascade,
+    expires_at timestamptz not null,
+    used_at    timestamptz,
+    created_at timestamptz not null default now()
+);
+
+create index if not exists image_uploads_post_id_idx on image_uploads (post_id);
@@ -0,0 +1,5 @@
+-- ### POST MEDIA
+alter table post_media
+    drop constraint if exists post_media_post_id_position_key;
+
+create index if not exists post_media_post_id_position_idx on post_media (post_id, position);
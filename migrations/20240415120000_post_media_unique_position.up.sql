@@ -0,0 +1,13 @@
+-- ### POST MEDIA
+-- Positions could repeat after a delete or concurrent adds, renumber them in their current order first.
+update post_media
+set position = numbered.position
+from (select id, row_number() over (partition by post_id order by position, created_at) - 1 as position
+      from post_media) as numbered
+where post_media.id = numbered.id;
+
+drop index if exists post_media_post_id_position_idx;
+
+-- Deferred, so reordering can swap positions within one statement.
+alter table post_media
+    add constraint post_media_post_id_position_key unique (post_id, position) deferrable initially deferred;
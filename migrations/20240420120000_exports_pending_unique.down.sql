@@ -0,0 +1,2 @@
+-- ### EXPORTS
+drop index if exists exports_user_id_pending_key;
@@ -0,0 +1,11 @@
+-- ### EXPORTS
+-- A user has at most one export being built, keep the newest of any pending duplicates.
+update exports
+set status = 'failed'
+where status = 'pending'
+  and id not in (select distinct on (user_id) id
+                 from exports
+                 where status = 'pending'
+                 order by user_id, created_at desc);
+
+create unique index if not exists exports_user_id_pending_key on exports (user_id) where status = 'pending';
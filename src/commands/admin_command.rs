@@ -0,0 +1,59 @@
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::config::migrations::check_schema;
+use crate::config::settings::Settings;
+use crate::container::Container;
+use crate::enums::error::Error;
+use crate::models::user::User;
+
+/// Runs an administrative command against the services of a fresh container, once the
+/// schema is known to be up to date.
+pub async fn run_admin<F>(settings: Arc<Settings>, command: F) -> ExitCode
+where
+  F: AsyncFnOnce(&Container) -> Result<(), Error>,
+{
+  let container = match Container::new(settings).await {
+    Ok(container) => container,
+    Err(err) => {
+      eprintln!("{}", err);
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let result = match check_schema(&container.pool).await {
+    Ok(()) => command(&container).await.map_err(|err| describe(&err)),
+    Err(err) => Err(err),
+  };
+  container.close().await;
+
+  match result {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(err) => {
+      eprintln!("{}", err);
+      ExitCode::FAILURE
+    }
+  }
+}
+
+/// The error with the fields that failed validation, one per line.
+fn describe(error: &Error) -> String {
+  let mut description = error.to_string();
+  for field_error in error.field_errors() {
+    description.push_str(&format!("\n  - {}", field_error.message));
+  }
+
+  description
+}
+
+/// Looks a user up by id, or by email when the argument is not a UUID.
+pub async fn find_user(container: &Container, user: &str) -> Result<User, Error> {
+  let found = match Uuid::parse_str(user) {
+    Ok(id) => container.user_service.get_one(id).await?,
+    Err(_) => container.user_service.get_by_email(user.to_string()).await?,
+  };
+
+  found.ok_or_else(|| Error::NotFound(format!("User {} not found.", user)))
+}
@@ -0,0 +1,46 @@
+use clap::{Parser, Subcommand};
+
+use crate::commands::migrate_command::MigrateAction;
+use crate::commands::post_command::PostAction;
+use crate::commands::storage_command::StorageAction;
+use crate::commands::tokens_command::TokensAction;
+use crate::commands::user_command::UserAction;
+
+/// REST API of the blog, and commands to administer it.
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+  #[command(subcommand)]
+  pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+  /// Runs the HTTP server, the default without a command.
+  Serve,
+  /// Applies, reverts or lists the database migrations embedded in the binary.
+  Migrate {
+    #[command(subcommand)]
+    action: MigrateAction,
+  },
+  /// Manages users.
+  User {
+    #[command(subcommand)]
+    action: UserAction,
+  },
+  /// Manages the sessions of users.
+  Tokens {
+    #[command(subcommand)]
+    action: TokensAction,
+  },
+  /// Maintains posts.
+  Post {
+    #[command(subcommand)]
+    action: PostAction,
+  },
+  /// Maintains the object storage.
+  Storage {
+    #[command(subcommand)]
+    action: StorageAction,
+  },
+}
@@ -0,0 +1,61 @@
+use std::process::ExitCode;
+
+use clap::Subcommand;
+
+use crate::config::migrations::{migration_status, revert_migration, run_migrations};
+use crate::config::pool::init_migration_pool;
+use crate::config::settings::Settings;
+
+#[derive(Subcommand)]
+pub enum MigrateAction {
+  /// Applies all pending migrations.
+  Up,
+  /// Reverts the latest applied migration.
+  Down,
+  /// Lists the migrations and whether they are applied.
+  Status,
+}
+
+pub async fn migrate(settings: &Settings, action: MigrateAction) -> ExitCode {
+  let pool = match init_migration_pool(settings).await {
+    Ok(pool) => pool,
+    Err(err) => {
+      eprintln!("Migration failed: {}", err);
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let result = match action {
+    MigrateAction::Up => run_migrations(&pool).await.map(|versions| {
+      if versions.is_empty() {
+        println!("Schema is up to date.");
+      }
+      for version in versions {
+        println!("Applied {}.", version);
+      }
+    }),
+    MigrateAction::Down => revert_migration(&pool).await.map(|version| match version {
+      Some(version) => println!("Reverted {}.", version),
+      None => println!("No migration is applied."),
+    }),
+    MigrateAction::Status => migration_status(&pool).await.map(|migrations| {
+      for migration in migrations {
+        let state = match (migration.applied, migration.checksum_matches) {
+          (false, _) => "pending",
+          (true, true) => "applied",
+          (true, false) => "changed",
+        };
+        println!("{} {:<8} {}", migration.version, state, migration.description);
+      }
+    }),
+  };
+  pool.close().await;
+
+  match result {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(err) => {
+      eprintln!("Migration failed: {}", err);
+      ExitCode::FAILURE
+    }
+  }
+}
@@ -0,0 +1,8 @@
+pub mod admin_command;
+pub mod cli;
+pub mod migrate_command;
+pub mod post_command;
+pub mod serve_command;
+pub mod storage_command;
+pub mod tokens_command;
+pub mod user_command;
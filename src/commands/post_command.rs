@@ -0,0 +1,28 @@
+use clap::Subcommand;
+
+use crate::container::Container;
+use crate::enums::error::Error;
+
+#[derive(Subcommand)]
+pub enum PostAction {
+  /// Regenerates the image variants of posts saved before variants existed.
+  Reindex {
+    /// Regenerates the variants of every post with an image.
+    #[arg(long)]
+    all: bool,
+  },
+}
+
+pub async fn post(container: &Container, action: PostAction) -> Result<(), Error> {
+  match action {
+    PostAction::Reindex { all } => {
+      let ids = container.post_service.reindex_images(all).await?;
+      for id in &ids {
+        println!("Reindexed {}.", id);
+      }
+      println!("Reindexed {} post(s).", ids.len());
+    }
+  }
+
+  Ok(())
+}
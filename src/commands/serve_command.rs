@@ -0,0 +1,132 @@
+use std::future::IntoFuture;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::demo::init_demo;
+use crate::config::migrations::{check_schema, run_migrations};
+use crate::config::pool::init_migration_pool;
+use crate::config::settings::Settings;
+use crate::container::Container;
+use crate::create_app::create_app;
+use crate::services::pending_deletion_service::spawn_pending_deletions;
+use crate::services::storage_gc_service::spawn_storage_gc;
+use crate::utils::shutdown_util::shutdown_signal;
+
+/// Brings the schema up to date when `auto_migrate` is set and refuses to serve when it is behind.
+/// Migrations run on their own pool, without the statement timeout.
+async fn prepare_schema(container: &Container) -> Result<(), String> {
+  if container.settings.auto_migrate {
+    let pool = init_migration_pool(&container.settings).await?;
+    let result = run_migrations(&pool).await;
+    pool.close().await;
+
+    let versions = result.map_err(|err| err.to_string())?;
+    if !versions.is_empty() {
+      tracing::info!(?versions, "Applied migrations");
+    }
+  }
+
+  check_schema(&container.pool).await
+}
+
+pub async fn serve(settings: Arc<Settings>, tracer_provider: Option<SdkTracerProvider>) -> ExitCode {
+  let container = match Container::new(settings.clone()).await {
+    Ok(container) => Arc::new(container),
+    Err(err) => {
+      tracing::error!("{}", err);
+      return ExitCode::FAILURE;
+    }
+  };
+
+  if let Err(err) = prepare_schema(&container).await {
+    tracing::error!("{}", err);
+    container.close().await;
+    return ExitCode::FAILURE;
+  }
+
+  // Builds run in the process that accepted them, so a pending export at startup was interrupted.
+  match container.export_service.fail_interrupted().await {
+    Ok(0) => {}
+    Ok(failed) => tracing::warn!(failed, "Marked interrupted exports failed"),
+    Err(err) => tracing::error!(error = %err, "Cleaning up interrupted exports failed"),
+  }
+
+  let user_service = container.user_service.clone();
+  init_demo(user_service).await;
+
+  let listener = match tokio::net::TcpListener::bind(&settings.bind_addr).await {
+    Ok(listener) => listener,
+    Err(err) => {
+      tracing::error!(addr = %settings.bind_addr, error = %err, "Cannot bind");
+      container.close().await;
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let shutdown = CancellationToken::new();
+  let mut tasks = vec![spawn_pending_deletions(
+    container.pending_deletion_service.clone(),
+    Duration::from_secs(settings.pending_deletions_interval_secs),
+    shutdown.clone(),
+  )];
+  if settings.storage_gc_interval_secs > 0 {
+    tasks.push(spawn_storage_gc(
+      container.storage_gc_service.clone(),
+      Duration::from_secs(settings.storage_gc_interval_secs),
+      settings.storage_gc_dry_run,
+      shutdown.clone(),
+    ));
+  }
+
+  tokio::spawn({
+    let shutdown = shutdown.clone();
+    async move {
+      shutdown_signal().await;
+      tracing::info!("Shutting down, draining in-flight requests");
+      shutdown.cancel();
+    }
+  });
+
+  tracing::info!(addr = %settings.bind_addr, "Listening");
+  let server = axum::serve(listener, create_app(container.clone()))
+    .with_graceful_shutdown(shutdown.clone().cancelled_owned())
+    .into_future();
+
+  // The server stops accepting connections on shutdown. Requests, background jobs and export builds share
+  // the drain timeout, whatever is still running after it is dropped.
+  let drain_timeout = Duration::from_secs(settings.shutdown_timeout_secs);
+  let stopped = async {
+    let served = server.await;
+    if let Err(err) = &served {
+      tracing::error!(error = %err, "Server failed");
+      shutdown.cancel();
+    }
+    for task in tasks {
+      let _ = task.await;
+    }
+    container.export_service.drain().await;
+    served.is_ok()
+  };
+  let mut exit_code = ExitCode::SUCCESS;
+  tokio::select! {
+    served = stopped => if !served {
+      exit_code = ExitCode::FAILURE;
+    },
+    _ = async { shutdown.cancelled().await; tokio::time::sleep(drain_timeout).await } => {
+      tracing::warn!(timeout_secs = settings.shutdown_timeout_secs, "Shutdown did not finish in time");
+    }
+  }
+
+  container.close().await;
+
+  if let Some(provider) = tracer_provider {
+    // Flushes the spans still queued, blocking until the exporter is done.
+    let _ = tokio::task::spawn_blocking(move || provider.shutdown()).await;
+  }
+
+  exit_code
+}
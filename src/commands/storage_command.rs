@@ -0,0 +1,33 @@
+use clap::Subcommand;
+
+use crate::container::Container;
+use crate::enums::error::Error;
+
+#[derive(Subcommand)]
+pub enum StorageAction {
+  /// Removes stored objects that nothing references anymore.
+  Gc {
+    /// Only lists the objects that would be removed.
+    #[arg(long)]
+    dry_run: bool,
+  },
+}
+
+pub async fn storage(container: &Container, action: StorageAction) -> Result<(), Error> {
+  match action {
+    StorageAction::Gc { dry_run } => {
+      let report = container.storage_gc_service.collect(dry_run).await?;
+      for key in &report.deleted_keys {
+        println!("{}", key);
+      }
+
+      let verb = if report.dry_run { "Would delete" } else { "Deleted" };
+      println!(
+        "{} {} of {} object(s), {} bytes.",
+        verb, report.deleted_keys.len(), report.scanned, report.reclaimed_bytes,
+      );
+    }
+  }
+
+  Ok(())
+}
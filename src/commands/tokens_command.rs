@@ -0,0 +1,27 @@
+use clap::Subcommand;
+
+use crate::commands::admin_command::find_user;
+use crate::container::Container;
+use crate::enums::error::Error;
+
+#[derive(Subcommand)]
+pub enum TokensAction {
+  /// Revokes the access and refresh tokens of every session of a user.
+  Revoke {
+    /// Id or email of the user.
+    #[arg(long)]
+    user: String,
+  },
+}
+
+pub async fn tokens(container: &Container, action: TokensAction) -> Result<(), Error> {
+  match action {
+    TokensAction::Revoke { user } => {
+      let user = find_user(container, &user).await?;
+      let revoked = container.token_service.delete_user_tokens(user.id).await?;
+      println!("Revoked {} token(s) of user {}.", revoked, user.id);
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,133 @@
+use std::io::{BufRead, IsTerminal, Write};
+
+use clap::Subcommand;
+
+use crate::commands::admin_command::find_user;
+use crate::container::Container;
+use crate::dtos::request::user_request_dto::UserRequestDto;
+use crate::enums::error::Error;
+use crate::enums::user_role::UserRole;
+use crate::models::user::CreateUser;
+use crate::traits::validate::Validate;
+use crate::utils::password_util::hash_password;
+
+#[derive(Subcommand)]
+pub enum UserAction {
+  /// Creates a user, the password is read from stdin.
+  Create {
+    #[arg(long)]
+    email: String,
+    #[arg(long)]
+    username: String,
+    #[arg(long, default_value = "user")]
+    role: UserRole,
+  },
+  /// Lists all users.
+  List,
+  /// Deletes a user with their posts and comments, and signs them out.
+  Delete {
+    /// Id or email of the user.
+    user: String,
+  },
+  /// Changes the role of a user and signs them out.
+  SetRole {
+    /// Id or email of the user.
+    user: String,
+    role: UserRole,
+  },
+  /// Sets a new password, read from stdin, and signs the user out.
+  ResetPassword {
+    /// Id or email of the user.
+    user: String,
+  },
+}
+
+/// Reads the password in clear from the first line of stdin, prompting when it is a terminal.
+fn read_password() -> Result<String, Error> {
+  let stdin = std::io::stdin();
+  if stdin.is_terminal() {
+    eprint!("Password: ");
+    let _ = std::io::stderr().flush();
+  }
+
+  let mut password = String::new();
+  stdin.lock()
+    .read_line(&mut password)
+    .map_err(|error| Error::internal("Cannot read the password.", error))?;
+
+  Ok(password.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Applies the same rules as the API to the user about to be written and hashes its password.
+fn validate_user(user: UserRequestDto) -> Result<String, Error> {
+  user.validate()?;
+
+  hash_password(user.password).map_err(|error| Error::internal("Cannot hash the password.", error))
+}
+
+pub async fn user(container: &Container, action: UserAction) -> Result<(), Error> {
+  let service = &container.user_service;
+
+  match action {
+    UserAction::Create { email, username, role } => {
+      if service.get_by_email(email.clone()).await?.is_some() {
+        return Err(Error::Conflict(format!("User {} already exists.", email)));
+      }
+
+      let password = validate_user(UserRequestDto {
+        email: email.clone(),
+        username: username.clone(),
+        password: read_password()?,
+      })?;
+      let user = service.create(CreateUser { email, username, password, role: role.as_str().to_string() }).await?;
+      println!("Created user {}.", user.id);
+    }
+    UserAction::List => {
+      for user in service.get_all().await? {
+        println!("{} {:<5} {} {} {}", user.id, user.role, user.email, user.username, user.created_at.to_rfc3339());
+      }
+    }
+    UserAction::Delete { user } => {
+      let user = find_user(container, &user).await?;
+      // Signed out first, so a failed revocation leaves no deleted user with live sessions.
+      let revoked = container.token_service.delete_user_tokens(user.id).await?;
+      service.delete(user.id).await.map_err(|error| Error::InternalServerError(format!(
+        "Revoked {} token(s) but could not delete user {}: {}",
+        revoked, user.id, error.message(),
+      )))?;
+      println!("Deleted user {} and revoked {} token(s).", user.id, revoked);
+    }
+    UserAction::SetRole { user, role } => {
+      let mut user = find_user(container, &user).await?;
+      user.role = role.as_str().to_string();
+      let user = service.update(user).await?;
+      // The role is part of the tokens, so it only applies once the user signs in again.
+      let revoked = container.token_service.delete_user_tokens(user.id).await.map_err(|error| {
+        Error::InternalServerError(format!(
+          "Changed the role of user {} but could not revoke their tokens, run `tokens revoke --user {}`: {}",
+          user.id, user.id, error.message(),
+        ))
+      })?;
+      println!("User {} is now {} and {} token(s) were revoked.", user.id, user.role, revoked);
+    }
+    UserAction::ResetPassword { user } => {
+      let mut user = find_user(container, &user).await?;
+      user.password = validate_user(UserRequestDto {
+        email: user.email.clone(),
+        username: user.username.clone(),
+        password: read_password()?,
+      })?;
+      let user = service.update(user).await?;
+      // Revoked after the update, so no session signed in with the old password survives.
+      let revoked = container.token_service.delete_user_tokens(user.id).await.map_err(|error| {
+        Error::InternalServerError(format!(
+          "Reset the password of user {} but could not revoke their tokens, run `tokens revoke --user {}`: {}",
+          user.id, user.id, error.message(),
+        ))
+      })?;
+      println!("Reset the password of user {} and revoked {} token(s).", user.id, revoked);
+    }
+  }
+
+  Ok(())
+}
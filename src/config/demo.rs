@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::enums::user_role::UserRole;
 use crate::models::user::CreateUser;
 use crate::services::user_service::UserService;
 use crate::utils::password_util::hash_password;
@@ -13,10 +14,10 @@ async fn create_user(service: Arc<dyn UserService>, username: String, email: Str
   if exists { return; }
 
   let password = hash_password(password).unwrap();
-  let user = CreateUser { email, username: username.clone(), password };
+  let user = CreateUser { email, username: username.clone(), password, role: UserRole::User.as_str().to_string() };
   service.create(user)
     .await
-    .expect(format!("Failed to create {}.", username).as_str());
+    .unwrap_or_else(|_| panic!("Failed to create {}.", username));
 }
 
 pub async fn init_demo(service: Arc<dyn UserService>) {
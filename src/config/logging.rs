@@ -0,0 +1,37 @@
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::config::settings::Settings;
+use crate::enums::log_format::LogFormat;
+
+/// Installs the global subscriber. Closing spans are logged too, with their busy and
+/// idle time, which is how service and repository calls get their timing. With a tracer
+/// provider the spans are exported over OTLP as well, filtered by `otlp_filter` rather than
+/// `log_filter` since service and repository spans are debug level.
+pub fn init_logging(settings: &Settings, tracer_provider: Option<&SdkTracerProvider>) {
+    let fmt_layer = tracing_subscriber::fmt::layer().with_span_events(FmtSpan::CLOSE);
+    let fmt_layer: Box<dyn Layer<Registry> + Send + Sync> = match settings.log_format {
+        LogFormat::Json => fmt_layer
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+        LogFormat::Pretty => fmt_layer.boxed(),
+    };
+
+    let export_layer = tracer_provider.map(|provider| {
+        tracing_opentelemetry::layer()
+            .with_tracer(provider.tracer("rust-blog"))
+            .with_filter(EnvFilter::new(&settings.otlp_filter))
+    });
+
+    tracing_subscriber::registry()
+        .with(fmt_layer.with_filter(EnvFilter::new(&settings.log_filter)))
+        .with(export_layer)
+        .init();
+}
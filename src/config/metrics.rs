@@ -0,0 +1,89 @@
+use prometheus::{
+    Encoder, Gauge, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+
+use crate::enums::error::Error;
+
+/// Prometheus metrics of the service, rendered by `GET /metrics`.
+pub struct Metrics {
+    registry: Registry,
+    pub http_requests_total: IntCounterVec,
+    pub http_request_duration_seconds: HistogramVec,
+    pub db_pool_connections: IntGaugeVec,
+    pub db_pool_max_connections: IntGauge,
+    pub db_pool_acquire_wait_seconds: Gauge,
+    pub redis_command_duration_seconds: HistogramVec,
+    pub store_operations_total: IntCounterVec,
+    pub logins_total: IntCounterVec,
+    pub posts_created_total: IntCounter,
+    pub comments_created_total: IntCounter,
+}
+
+impl Metrics {
+    pub fn render(&self) -> Result<String, Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .map_err(|error| Error::internal("Error while encoding metrics.", error))?;
+
+        String::from_utf8(buffer)
+            .map_err(|error| Error::internal("Error while encoding metrics.", error))
+    }
+}
+
+fn register<T: prometheus::core::Collector + Clone + 'static>(registry: &Registry, collector: T) -> T {
+    registry
+        .register(Box::new(collector.clone()))
+        .expect("Metric registered twice.");
+    collector
+}
+
+pub fn init_metrics() -> Metrics {
+    let registry = Registry::new_custom(Some("rustblog".to_string()), None).expect("Invalid metrics prefix.");
+
+    Metrics {
+        http_requests_total: register(&registry, IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by method, route and status."),
+            &["method", "route", "status"],
+        ).unwrap()),
+        http_request_duration_seconds: register(&registry, HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency by method, route and status."),
+            &["method", "route", "status"],
+        ).unwrap()),
+        db_pool_connections: register(&registry, IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Postgres pool connections by state, sampled on scrape."),
+            &["state"],
+        ).unwrap()),
+        db_pool_max_connections: register(&registry, IntGauge::new(
+            "db_pool_max_connections",
+            "Configured maximum of Postgres pool connections.",
+        ).unwrap()),
+        db_pool_acquire_wait_seconds: register(&registry, Gauge::new(
+            "db_pool_acquire_wait_seconds",
+            "Time taken to acquire a Postgres connection, sampled on scrape.",
+        ).unwrap()),
+        redis_command_duration_seconds: register(&registry, HistogramVec::new(
+            HistogramOpts::new("redis_command_duration_seconds", "Redis command latency by command.")
+                .buckets(vec![0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0]),
+            &["command"],
+        ).unwrap()),
+        store_operations_total: register(&registry, IntCounterVec::new(
+            Opts::new("store_operations_total", "Object store operations by operation and result."),
+            &["operation", "result"],
+        ).unwrap()),
+        logins_total: register(&registry, IntCounterVec::new(
+            Opts::new("logins_total", "Login attempts by result."),
+            &["result"],
+        ).unwrap()),
+        posts_created_total: register(&registry, IntCounter::new(
+            "posts_created_total",
+            "Posts created.",
+        ).unwrap()),
+        comments_created_total: register(&registry, IntCounter::new(
+            "comments_created_total",
+            "Comments created.",
+        ).unwrap()),
+        registry,
+    }
+}
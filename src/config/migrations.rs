@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use sqlx::PgPool;
+use sqlx::migrate::{Migrate, MigrateError, Migration, Migrator};
+
+use crate::models::migration_status::MigrationStatus;
+
+/// Migrations in `migrations/`, embedded at build time.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+fn up_migrations() -> impl Iterator<Item = &'static Migration> {
+    MIGRATOR.iter().filter(|migration| !migration.migration_type.is_down_migration())
+}
+
+/// Embedded migrations in version order and whether each is applied.
+pub async fn migration_status(pool: &PgPool) -> Result<Vec<MigrationStatus>, MigrateError> {
+    let mut connection = pool.acquire().await?;
+    connection.ensure_migrations_table().await?;
+    let applied: HashMap<_, _> = connection.list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| (migration.version, migration.checksum))
+        .collect();
+
+    Ok(up_migrations()
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            applied: applied.contains_key(&migration.version),
+            checksum_matches: applied.get(&migration.version).is_none_or(|checksum| *checksum == migration.checksum),
+        })
+        .collect())
+}
+
+/// Applies the pending migrations and returns their versions. Concurrent callers, like
+/// replicas starting together, are serialised by an advisory lock.
+pub async fn run_migrations(pool: &PgPool) -> Result<Vec<i64>, MigrateError> {
+    let pending: Vec<i64> = migration_status(pool)
+        .await?
+        .into_iter()
+        .filter(|status| !status.applied)
+        .map(|status| status.version)
+        .collect();
+
+    MIGRATOR.run(pool).await?;
+
+    Ok(pending)
+}
+
+/// Reverts the latest applied migration and returns its version, if any was applied.
+pub async fn revert_migration(pool: &PgPool) -> Result<Option<i64>, MigrateError> {
+    let mut applied: Vec<i64> = migration_status(pool)
+        .await?
+        .into_iter()
+        .filter(|status| status.applied)
+        .map(|status| status.version)
+        .collect();
+
+    let Some(latest) = applied.pop() else {
+        return Ok(None);
+    };
+    MIGRATOR.undo(pool, applied.last().copied().unwrap_or(0)).await?;
+
+    Ok(Some(latest))
+}
+
+/// Fails unless every embedded migration is applied unchanged, so the server never runs
+/// against a schema older than the code expects.
+pub async fn check_schema(pool: &PgPool) -> Result<(), String> {
+    let mut connection = pool.acquire().await.map_err(|err| err.to_string())?;
+    // A database that was never migrated has no migrations table yet.
+    connection.ensure_migrations_table().await.map_err(|err| err.to_string())?;
+    if let Some(version) = connection.dirty_version().await.map_err(|err| err.to_string())? {
+        return Err(format!("Migration {} did not complete, the schema has to be repaired by hand.", version));
+    }
+
+    let status = migration_status(pool).await.map_err(|err| err.to_string())?;
+    if let Some(changed) = status.iter().find(|status| status.applied && !status.checksum_matches) {
+        return Err(format!("Migration {} was changed after it was applied.", changed.version));
+    }
+
+    let pending: Vec<_> = status.iter().filter(|status| !status.applied).collect();
+    if let Some(latest) = pending.last() {
+        return Err(format!(
+            "Database schema is behind, {} migration(s) up to {} are pending. Run `rust-blog migrate up` or set AUTO_MIGRATE.",
+            pending.len(),
+            latest.version,
+        ));
+    }
+
+    Ok(())
+}
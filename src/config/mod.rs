@@ -1,5 +1,9 @@
 pub mod pool;
 pub mod redis;
 pub mod demo;
+pub mod metrics;
 pub mod settings;
 pub mod store;
+pub mod logging;
+pub mod telemetry;
+pub mod migrations;
@@ -1,10 +1,80 @@
-use sqlx::{ PgPool};
-use sqlx::postgres::PgPoolOptions;
-use crate::config::settings::{Settings};
-
-pub async fn init_pool(settings: Settings) -> PgPool {
-    let dsn = settings.postgres_dsn.as_str();
-    PgPoolOptions::new()
-        .max_connections(5)
-        .connect(dsn).await.expect("Database connection failed.")
-}
\ No newline at end of file
+use std::str::FromStr;
+use std::time::Duration;
+
+use sqlx::PgPool;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+
+use crate::config::settings::Settings;
+
+// Startup retries wait 1 s, 2 s, 4 s, ... capped at 30 s.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+// SQLSTATE of "the database system is starting up".
+const CANNOT_CONNECT_NOW: &str = "57P03";
+
+/// Whether connecting may succeed later, e.g. while the database container is still starting.
+/// Anything else, like wrong credentials, fails at once.
+fn is_transient(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Database(error) => error.code().as_deref() == Some(CANNOT_CONNECT_NOW),
+        _ => false,
+    }
+}
+
+/// Creates the pool shared by all repositories, its statements are bounded by
+/// `postgres_statement_timeout_ms`.
+pub async fn init_pool(settings: &Settings) -> Result<PgPool, String> {
+    let mut connect_options = parse_dsn(settings)?;
+    if settings.postgres_statement_timeout_ms > 0 {
+        connect_options = connect_options
+            .options([("statement_timeout", settings.postgres_statement_timeout_ms.to_string())]);
+    }
+
+    connect(settings, connect_options).await
+}
+
+/// Creates a pool for applying and reverting migrations, which may rewrite large tables
+/// and so run without the statement timeout.
+pub async fn init_migration_pool(settings: &Settings) -> Result<PgPool, String> {
+    let connect_options = parse_dsn(settings)?;
+
+    connect(settings, connect_options).await
+}
+
+fn parse_dsn(settings: &Settings) -> Result<PgConnectOptions, String> {
+    PgConnectOptions::from_str(&settings.postgres_dsn).map_err(|error| format!("Database DSN is invalid: {}", error))
+}
+
+/// Each attempt keeps retrying refused connections for `postgres_acquire_timeout_secs`,
+/// transient failures are then retried with backoff up to `postgres_connect_retries` times
+/// before giving up.
+async fn connect(settings: &Settings, connect_options: PgConnectOptions) -> Result<PgPool, String> {
+    let idle_timeout = settings.postgres_idle_timeout_secs;
+    let pool_options = PgPoolOptions::new()
+        .max_connections(settings.postgres_max_connections)
+        .min_connections(settings.postgres_min_connections)
+        .acquire_timeout(Duration::from_secs(settings.postgres_acquire_timeout_secs))
+        .idle_timeout((idle_timeout > 0).then(|| Duration::from_secs(idle_timeout)));
+
+    let mut attempt = 0;
+    let mut delay = INITIAL_RETRY_DELAY;
+    loop {
+        match pool_options.clone().connect_with(connect_options.clone()).await {
+            Ok(pool) => return Ok(pool),
+            Err(error) if attempt < settings.postgres_connect_retries && is_transient(&error) => {
+                attempt += 1;
+                tracing::warn!(
+                    attempt,
+                    retries = settings.postgres_connect_retries,
+                    delay_secs = delay.as_secs(),
+                    error = %error,
+                    "Database is not reachable yet, retrying",
+                );
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RETRY_DELAY);
+            }
+            Err(error) => return Err(format!("Database connection failed: {}", error)),
+        }
+    }
+}
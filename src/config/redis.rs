@@ -1,9 +1,37 @@
-use redis::aio::Connection;
+use std::time::Duration;
+
+use redis::IntoConnectionInfo;
+use redis::aio::ConnectionManager;
 
 use crate::config::settings::Settings;
 
-pub async fn init_redis(settings: Settings) -> Connection {
-    let addr = settings.redis_addr.clone();
-    let client = redis::Client::open(addr).expect("Redis failed to create client.");
-    client.get_async_connection().await.expect("Redis failed to create connection.")
-}
\ No newline at end of file
+// Reconnect delays grow as 200 ms, 400 ms, 800 ms, ... for up to six attempts.
+const RETRY_EXPONENT_BASE: u64 = 2;
+const RETRY_FACTOR_MS: u64 = 100;
+const RETRIES: usize = 6;
+// Upper bound for the first connection, including its retries.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Connects a multiplexed connection that is shared by cloning and re-established with
+/// backoff when it drops, e.g. after a Redis restart. `rediss://` addresses use TLS and
+/// `redis_password`, when set, takes precedence over a password in the address. Fails on
+/// an invalid address or once the first connection could not be made within its retries.
+pub async fn init_redis(settings: &Settings) -> Result<ConnectionManager, String> {
+    let mut info = settings
+        .redis_addr
+        .as_str()
+        .into_connection_info()
+        .map_err(|error| format!("Redis address is invalid: {}", error))?;
+    if !settings.redis_password.is_empty() {
+        info.redis.password = Some(settings.redis_password.clone());
+    }
+
+    let client = redis::Client::open(info).map_err(|error| format!("Redis failed to create client: {}", error))?;
+    let connect = ConnectionManager::new_with_backoff(client, RETRY_EXPONENT_BASE, RETRY_FACTOR_MS, RETRIES);
+
+    match tokio::time::timeout(CONNECT_TIMEOUT, connect).await {
+        Ok(Ok(manager)) => Ok(manager),
+        Ok(Err(error)) => Err(format!("Redis connection failed: {}", error)),
+        Err(_) => Err(format!("Redis connection timed out after {} s.", CONNECT_TIMEOUT.as_secs())),
+    }
+}
@@ -6,7 +6,7 @@ use s3::region::Region;
 
 use crate::config::settings::Settings;
 
-pub fn init_store(settings: Settings) -> Bucket {
+pub fn init_store(settings: &Settings) -> Bucket {
   let credentials = Credentials::new(
     Some(settings.minio_access_key.as_str()),
     Some(settings.minio_secret_key.as_str()),
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use opentelemetry::global;
+use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+
+use crate::config::settings::Settings;
+
+// Appended to `otlp_endpoint`, which is the collector base URL as in `OTEL_EXPORTER_OTLP_ENDPOINT`.
+const TRACES_PATH: &str = "/v1/traces";
+// Bounds every export, including the final flush on shutdown.
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Builds the OTLP/HTTP span exporter when `otlp_endpoint` is set. Without it no provider
+/// is created and the global propagator stays a no-op, so incoming `traceparent` headers
+/// are ignored and nothing is exported.
+pub fn init_tracer_provider(settings: &Settings) -> Option<SdkTracerProvider> {
+    if settings.otlp_endpoint.is_empty() {
+        return None;
+    }
+
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_protocol(Protocol::HttpBinary)
+        .with_endpoint(format!("{}{}", settings.otlp_endpoint.trim_end_matches('/'), TRACES_PATH))
+        .with_timeout(EXPORT_TIMEOUT)
+        .build()
+        .expect("Cannot create the OTLP exporter.");
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        // Follows the sampling decision of an incoming `traceparent`, new traces are sampled by ratio.
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(settings.otlp_sample_ratio))))
+        .with_resource(Resource::builder().with_service_name(settings.otlp_service_name.clone()).build())
+        .build();
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    Some(provider)
+}
@@ -1,77 +1,194 @@
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
-use redis::aio::Connection;
+use redis::aio::ConnectionManager;
 use s3::Bucket;
 use sqlx::PgPool;
-use tokio::sync::Mutex;
 
+use crate::config::metrics::{init_metrics, Metrics};
 use crate::config::pool::init_pool;
 use crate::config::redis::init_redis;
-use crate::config::settings::init_settings;
+use crate::config::settings::Settings;
 use crate::config::store::init_store;
+use crate::enums::store_backend::StoreBackend;
 use crate::repository::comment_repository::{CommentRepository, CommentRepositoryImpl};
+use crate::repository::export_repository::{ExportRepository, ExportRepositoryImpl};
+use crate::repository::health_repository::{HealthRepository, HealthRepositoryImpl};
+use crate::repository::image_upload_repository::{ImageUploadRepository, ImageUploadRepositoryImpl};
+use crate::repository::pending_deletion_repository::{PendingDeletionRepository, PendingDeletionRepositoryImpl};
+use crate::repository::post_media_repository::{PostMediaRepository, PostMediaRepositoryImpl};
 use crate::repository::post_repository::{PostRepository, PostRepositoryImpl};
+use crate::repository::storage_repository::{StorageRepository, StorageRepositoryImpl};
 use crate::repository::token_repository::{TokenRepository, TokenRepositoryImpl};
 use crate::repository::user_repository::{UserRepository, UserRepositoryImpl};
 use crate::services::auth_service::{AuthService, AuthServiceImpl};
 use crate::services::comment_service::{CommentService, CommentServiceImpl};
+use crate::services::export_service::{ExportService, ExportServiceImpl};
+use crate::services::health_service::{HealthService, HealthServiceImpl};
+use crate::services::image_service::{ImageService, ImageServiceImpl};
+use crate::services::local_store_service::LocalStoreServiceImpl;
+use crate::services::metrics_service::{MetricsService, MetricsServiceImpl};
+use crate::services::memory_store_service::MemoryStoreServiceImpl;
+use crate::services::pending_deletion_service::{PendingDeletionService, PendingDeletionServiceImpl};
 use crate::services::post_service::{PostService, PostServiceImpl};
+use crate::services::storage_gc_service::{StorageGcService, StorageGcServiceImpl};
 use crate::services::store_service::{StoreService, StoreServiceImpl};
 use crate::services::token_service::{TokenService, TokenServiceImpl};
 use crate::services::user_service::{UserService, UserServiceImpl};
 
+const REDIS_QUIT_TIMEOUT: Duration = Duration::from_secs(1);
+
 pub struct Container {
+  pub settings: Arc<Settings>,
+  pub metrics: Arc<Metrics>,
+  pub pool: Arc<PgPool>,
+  pub redis: ConnectionManager,
   pub token_service: Arc<dyn TokenService>,
   pub user_service: Arc<dyn UserService>,
   pub auth_service: Arc<dyn AuthService>,
   pub store_service: Arc<dyn StoreService>,
   pub comment_service: Arc<dyn CommentService>,
   pub post_service: Arc<dyn PostService>,
+  pub export_service: Arc<dyn ExportService>,
+  pub image_service: Arc<dyn ImageService>,
+  pub storage_gc_service: Arc<dyn StorageGcService>,
+  pub pending_deletion_service: Arc<dyn PendingDeletionService>,
+  pub health_service: Arc<dyn HealthService>,
+  pub metrics_service: Arc<dyn MetricsService>,
 }
 
 impl Container {
-  pub async fn new() -> Self {
+  pub async fn new(settings: Arc<Settings>) -> Result<Self, String> {
+    let metrics = Arc::new(init_metrics());
+    let pool = Container::create_pool(&settings).await?;
+    let redis = match init_redis(&settings).await {
+      Ok(redis) => redis,
+      Err(err) => {
+        pool.close().await;
+        return Err(err);
+      }
+    };
+
     // Repositories
     let comment_repository: Arc<dyn CommentRepository> = Arc::new(
-      CommentRepositoryImpl::new(Container::create_pool().await)
+      CommentRepositoryImpl::new(pool.clone())
+    );
+    let export_repository: Arc<dyn ExportRepository> = Arc::new(
+      ExportRepositoryImpl::new(pool.clone())
+    );
+    let health_repository: Arc<dyn HealthRepository> = Arc::new(
+      HealthRepositoryImpl::new(pool.clone())
+    );
+    let image_upload_repository: Arc<dyn ImageUploadRepository> = Arc::new(
+      ImageUploadRepositoryImpl::new(pool.clone())
     );
     let post_repository: Arc<dyn PostRepository> = Arc::new(
-      PostRepositoryImpl::new(Container::create_pool().await)
+      PostRepositoryImpl::new(pool.clone())
+    );
+    let pending_deletion_repository: Arc<dyn PendingDeletionRepository> = Arc::new(
+      PendingDeletionRepositoryImpl::new(pool.clone())
+    );
+    let post_media_repository: Arc<dyn PostMediaRepository> = Arc::new(
+      PostMediaRepositoryImpl::new(pool.clone())
+    );
+    let storage_repository: Arc<dyn StorageRepository> = Arc::new(
+      StorageRepositoryImpl::new(pool.clone())
     );
     let token_repository: Arc<dyn TokenRepository> = Arc::new(
-      TokenRepositoryImpl::new(Container::create_redis().await)
+      TokenRepositoryImpl::new(redis.clone(), Duration::from_millis(settings.redis_timeout_ms), metrics.clone())
     );
     let user_repository: Arc<dyn UserRepository> = Arc::new(
-      UserRepositoryImpl::new(Container::create_pool().await)
+      UserRepositoryImpl::new(pool.clone())
     );
 
     // Services
     let token_service: Arc<dyn TokenService> = Arc::new(
-      TokenServiceImpl::new(Arc::new(init_settings()), token_repository)
+      TokenServiceImpl::new(settings.clone(), token_repository.clone())
     );
     let user_service: Arc<dyn UserService> = Arc::new(
       UserServiceImpl::new(user_repository)
     );
     let auth_service: Arc<dyn AuthService> = Arc::new(
-      AuthServiceImpl::new(user_service.clone(), token_service.clone(), Arc::new(init_settings()))
+      AuthServiceImpl::new(user_service.clone(), token_service.clone(), settings.clone(), metrics.clone())
+    );
+    let comment_service = Arc::new(CommentServiceImpl::new(comment_repository, metrics.clone()));
+    let store_service = Container::create_store_service(&settings, metrics.clone());
+    let image_service: Arc<dyn ImageService> = Arc::new(
+      ImageServiceImpl::new(store_service.clone(), settings.image_allowed_sizes.clone())
     );
-    let comment_service = Arc::new(CommentServiceImpl::new(comment_repository));
-    let store_service: Arc<dyn StoreService> = Arc::new(
-      StoreServiceImpl::new(Container::create_store())
+    let pending_deletion_service: Arc<dyn PendingDeletionService> = Arc::new(
+      PendingDeletionServiceImpl::new(pending_deletion_repository, store_service.clone())
     );
-    let post_service = Arc::new(PostServiceImpl::new(post_repository, store_service.clone()));
+    let post_service = Arc::new(PostServiceImpl::new(
+      post_repository,
+      post_media_repository,
+      image_upload_repository,
+      store_service.clone(),
+      pending_deletion_service.clone(),
+      metrics.clone(),
+    ));
+    let export_service: Arc<dyn ExportService> = Arc::new(ExportServiceImpl::new(
+      export_repository,
+      user_service.clone(),
+      post_service.clone(),
+      comment_service.clone(),
+      store_service.clone(),
+    ));
+    let storage_gc_service: Arc<dyn StorageGcService> = Arc::new(StorageGcServiceImpl::new(
+      storage_repository,
+      store_service.clone(),
+      chrono::Duration::seconds(settings.storage_gc_grace_secs),
+    ));
 
-    Container {
+    let metrics_service: Arc<dyn MetricsService> = Arc::new(
+      MetricsServiceImpl::new(metrics.clone(), health_repository.clone())
+    );
+    let health_service: Arc<dyn HealthService> = Arc::new(HealthServiceImpl::new(
+      health_repository,
+      token_repository,
+      store_service.clone(),
+    ));
+
+    Ok(Container {
+      settings,
+      metrics,
+      pool,
+      redis,
       token_service,
       user_service,
       auth_service,
       store_service,
       comment_service,
       post_service,
-    }
+      export_service,
+      image_service,
+      storage_gc_service,
+      pending_deletion_service,
+      health_service,
+      metrics_service,
+    })
   }
 
-  async fn create_pool() -> Arc<PgPool> { Arc::new(init_pool(init_settings()).await) }
-  fn create_store() -> Arc<Bucket> { Arc::new(init_store(init_settings())) }
-  async fn create_redis() -> Arc<Mutex<Connection>> { Arc::new(Mutex::new(init_redis(init_settings()).await)) }
+  /// Closes the database pool, waiting for checked out connections, and the Redis connection.
+  pub async fn close(&self) {
+    self.pool.close().await;
+
+    // A dead connection would otherwise hold up the exit until the socket times out.
+    let _ = tokio::time::timeout(REDIS_QUIT_TIMEOUT, async {
+      let mut redis = self.redis.clone();
+      let _: Result<(), _> = redis::cmd("QUIT").query_async(&mut redis).await;
+    }).await;
+  }
+
+  async fn create_pool(settings: &Settings) -> Result<Arc<PgPool>, String> { init_pool(settings).await.map(Arc::new) }
+  fn create_store(settings: &Settings) -> Arc<Bucket> { Arc::new(init_store(settings)) }
+
+  fn create_store_service(settings: &Settings, metrics: Arc<Metrics>) -> Arc<dyn StoreService> {
+    match settings.store_backend {
+      StoreBackend::S3 => Arc::new(StoreServiceImpl::new(Container::create_store(settings), metrics)),
+      StoreBackend::Local => Arc::new(LocalStoreServiceImpl::new(PathBuf::from(&settings.store_local_path))),
+      StoreBackend::Memory => Arc::new(MemoryStoreServiceImpl::new()),
+    }
+  }
 }
@@ -1,19 +1,31 @@
 use std::sync::Arc;
 
 use axum::{Extension, Router};
+use axum::extract::DefaultBodyLimit;
 use axum::middleware::from_fn;
-use axum::routing::{delete, get, post, put};
+use axum::routing::{delete, get, patch, post, put};
 use tower_http::services::{ServeDir, ServeFile};
+use tower_http::trace::TraceLayer;
 
-use crate::config::settings::init_settings;
 use crate::container::Container;
 use crate::handlers::auth_handler::AuthHandler;
 use crate::handlers::comment_handler::CommentHandler;
+use crate::handlers::export_handler::ExportHandler;
+use crate::handlers::health_handler::HealthHandler;
+use crate::handlers::metrics_handler::MetricsHandler;
 use crate::handlers::post_handler::PostHandler;
 use crate::handlers::proxy_handler::ProxyHandler;
 use crate::middlewares::jwt_auth_middleware::jwt_auth_middleware;
+use crate::middlewares::metrics_middleware::metrics_middleware;
+use crate::middlewares::request_id_middleware::request_id_middleware;
+use crate::middlewares::trace_middleware::{make_request_span, record_response_status};
+
+// Room for multipart boundaries and part headers on top of the file itself.
+const MULTIPART_OVERHEAD: usize = 16 * 1024;
 
 pub fn create_app(container: Arc<Container>) -> Router {
+  let settings = container.settings.clone();
+
   let routes = Router::new()
     // Swagger
     .nest_service("/docs", ServeDir::new("./assets/swagger-ui/"))
@@ -21,7 +33,10 @@ pub fn create_app(container: Arc<Container>) -> Router {
 
     // Auth
     .route("/auth/login", post(AuthHandler::login))
-    .route("/auth/token/refresh", post(AuthHandler::refresh_token));
+    .route("/auth/token/refresh", post(AuthHandler::refresh_token))
+
+    // Exports
+    .route("/exports/:id/download", get(ExportHandler::download));
 
   let routes_with_auth = Router::new()
     // Auth
@@ -32,13 +47,32 @@ pub fn create_app(container: Arc<Container>) -> Router {
     .route("/posts/:id", get(PostHandler::get_one))
     .route("/posts", post(PostHandler::create))
     .route("/posts/:id", put(PostHandler::update))
-    .route("/posts/:id/image", put(PostHandler::add_image))
+    .route("/posts/:id", patch(PostHandler::patch))
+    .route(
+      "/posts/:id/image",
+      put(PostHandler::add_image).layer(DefaultBodyLimit::max(settings.max_upload_size + MULTIPART_OVERHEAD)),
+    )
+    .route("/posts/:id/image/upload-url", post(PostHandler::create_image_upload))
+    .route("/posts/:id/image/confirm", post(PostHandler::confirm_image_upload))
     .route("/posts/:id", delete(PostHandler::delete))
+    .route("/posts/:id/media", get(PostHandler::get_media))
+    .route(
+      "/posts/:id/media",
+      post(PostHandler::add_media).layer(DefaultBodyLimit::max(settings.max_upload_size + MULTIPART_OVERHEAD)),
+    )
+    .route("/posts/:id/media/order", put(PostHandler::reorder_media))
+    .route("/posts/:id/media/:media_id", put(PostHandler::update_media))
+    .route("/posts/:id/media/:media_id", delete(PostHandler::delete_media))
 
     // Comments
     .route("/posts/:id/comments", get(CommentHandler::get_all))
     .route("/posts/:id/comments", post(CommentHandler::create))
     .route("/posts/:post_id/comments/:id", put(CommentHandler::update))
+    .route("/posts/:post_id/comments/:id", patch(CommentHandler::patch))
+
+    // Me
+    .route("/me/export", post(ExportHandler::create))
+    .route("/me/export/:id", get(ExportHandler::get_one))
 
     .route_layer(from_fn(jwt_auth_middleware));
 
@@ -46,13 +80,32 @@ pub fn create_app(container: Arc<Container>) -> Router {
     .nest("/api", routes)
     .nest("/api", routes_with_auth)
     // File proxy
-    .route("/assets/images/:id", get(ProxyHandler::serve_public_bucket));
+    .route("/assets/images/:id", get(ProxyHandler::serve_public_bucket))
+
+    // Health
+    .route("/health/live", get(HealthHandler::live))
+    .route("/health/ready", get(HealthHandler::ready))
+
+    // Metrics
+    .route("/metrics", get(MetricsHandler::get));
 
-  app.layer(Extension(Arc::new(init_settings())))
+  app.layer(from_fn(metrics_middleware))
+    .layer(Extension(settings))
+    .layer(Extension(container.metrics.clone()))
     .layer(Extension(container.token_service.clone()))
     .layer(Extension(container.user_service.clone()))
     .layer(Extension(container.auth_service.clone()))
     .layer(Extension(container.store_service.clone()))
     .layer(Extension(container.post_service.clone()))
     .layer(Extension(container.comment_service.clone()))
+    .layer(Extension(container.export_service.clone()))
+    .layer(Extension(container.image_service.clone()))
+    .layer(Extension(container.health_service.clone()))
+    .layer(Extension(container.metrics_service.clone()))
+    .layer(
+      TraceLayer::new_for_http()
+        .make_span_with(make_request_span)
+        .on_response(record_response_status),
+    )
+    .layer(from_fn(request_id_middleware))
 }
\ No newline at end of file
@@ -0,0 +1,31 @@
+use serde::Deserialize;
+
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+use crate::utils::serde_util::deserialize_some;
+use crate::utils::validation_util::Validator;
+
+const MAX_BODY_LENGTH: usize = 5_000;
+
+/// JSON Merge Patch (RFC 7396) of a comment: absent fields are left untouched.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentPatchRequestDto {
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub body: Option<Option<String>>,
+}
+
+impl Validate for CommentPatchRequestDto {
+    fn validate(&self) -> Result<(), Error> {
+        let mut validator = Validator::new();
+        validator.not_null("body", &self.body);
+
+        if let Some(Some(body)) = &self.body {
+            validator
+                .required("body", body)
+                .max_length("body", body, MAX_BODY_LENGTH);
+        }
+
+        validator.finish()
+    }
+}
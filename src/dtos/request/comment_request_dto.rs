@@ -1,9 +1,25 @@
 use serde::Deserialize;
 use uuid::Uuid;
 
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+use crate::utils::validation_util::Validator;
+
+const MAX_BODY_LENGTH: usize = 5_000;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CommentRequestDto {
+    #[serde(default)]
     pub body: String,
     pub post_id: Uuid,
-}
\ No newline at end of file
+}
+
+impl Validate for CommentRequestDto {
+    fn validate(&self) -> Result<(), Error> {
+        Validator::new()
+            .required("body", &self.body)
+            .max_length("body", &self.body, MAX_BODY_LENGTH)
+            .finish()
+    }
+}
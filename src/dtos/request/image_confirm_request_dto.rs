@@ -0,0 +1,18 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageConfirmRequestDto {
+    pub key: Uuid,
+}
+
+impl Validate for ImageConfirmRequestDto {
+    // The key is checked by deserializing it as a UUID.
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
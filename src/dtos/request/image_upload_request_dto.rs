@@ -0,0 +1,29 @@
+use serde::Deserialize;
+
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+use crate::utils::image_util::IMAGE_CONTENT_TYPES;
+use crate::utils::validation_util::Validator;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageUploadRequestDto {
+    pub content_type: String,
+    pub content_length: u64,
+}
+
+impl Validate for ImageUploadRequestDto {
+    // The upper bound of the length comes from the settings, it is checked by the service.
+    fn validate(&self) -> Result<(), Error> {
+        let mut validator = Validator::new();
+        if !IMAGE_CONTENT_TYPES.contains(&self.content_type.as_str()) {
+            let message = format!("contentType must be one of {}.", IMAGE_CONTENT_TYPES.join(", "));
+            validator.add("contentType", "unsupported", &message);
+        }
+        if self.content_length == 0 {
+            validator.add("contentLength", "required", "contentLength must be greater than 0.");
+        }
+
+        validator.finish()
+    }
+}
@@ -1,7 +1,26 @@
 use serde::Deserialize;
 
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+use crate::utils::validation_util::Validator;
+
+const MAX_PASSWORD_LENGTH: usize = 128;
+
 #[derive(Debug, Deserialize)]
 pub struct LoginRequestDto {
+    #[serde(default)]
     pub email: String,
+    #[serde(default)]
     pub password: String,
 }
+
+impl Validate for LoginRequestDto {
+    fn validate(&self) -> Result<(), Error> {
+        Validator::new()
+            .required("email", &self.email)
+            .email("email", &self.email)
+            .required("password", &self.password)
+            .max_length("password", &self.password, MAX_PASSWORD_LENGTH)
+            .finish()
+    }
+}
@@ -1,4 +1,13 @@
+pub mod comment_patch_request_dto;
 pub mod comment_request_dto;
+pub mod export_download_request_dto;
+pub mod image_confirm_request_dto;
+pub mod image_transform_request_dto;
+pub mod image_upload_request_dto;
 pub mod login_request_dto;
+pub mod post_media_order_request_dto;
+pub mod post_media_request_dto;
+pub mod post_patch_request_dto;
 pub mod post_request_dto;
-pub mod token_refresh_request_dto;
\ No newline at end of file
+pub mod token_refresh_request_dto;
+pub mod user_request_dto;
@@ -0,0 +1,26 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+use crate::utils::validation_util::Validator;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostMediaOrderRequestDto {
+    pub ids: Vec<Uuid>,
+}
+
+impl Validate for PostMediaOrderRequestDto {
+    fn validate(&self) -> Result<(), Error> {
+        let mut validator = Validator::new();
+
+        if self.ids.iter().collect::<HashSet<_>>().len() != self.ids.len() {
+            validator.add("ids", "duplicate", "ids must not contain duplicates.");
+        }
+
+        validator.finish()
+    }
+}
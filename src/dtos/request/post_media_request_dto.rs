@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+use crate::utils::validation_util::Validator;
+
+const MAX_ALT_TEXT_LENGTH: usize = 300;
+const MAX_CAPTION_LENGTH: usize = 2_000;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostMediaRequestDto {
+    #[serde(default)]
+    pub alt_text: String,
+    #[serde(default)]
+    pub caption: String,
+}
+
+impl Validate for PostMediaRequestDto {
+    fn validate(&self) -> Result<(), Error> {
+        Validator::new()
+            .max_length("altText", &self.alt_text, MAX_ALT_TEXT_LENGTH)
+            .max_length("caption", &self.caption, MAX_CAPTION_LENGTH)
+            .finish()
+    }
+}
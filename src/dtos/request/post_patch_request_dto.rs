@@ -0,0 +1,46 @@
+use serde::Deserialize;
+
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+use crate::utils::serde_util::deserialize_some;
+use crate::utils::validation_util::Validator;
+
+const MAX_TITLE_LENGTH: usize = 200;
+const MAX_BODY_LENGTH: usize = 50_000;
+
+/// JSON Merge Patch (RFC 7396) of a post: absent fields are left untouched and `null` clears.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostPatchRequestDto {
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub title: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub body: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub image: Option<Option<String>>,
+}
+
+impl Validate for PostPatchRequestDto {
+    fn validate(&self) -> Result<(), Error> {
+        let mut validator = Validator::new();
+        validator
+            .not_null("title", &self.title)
+            .not_null("body", &self.body);
+
+        if let Some(Some(title)) = &self.title {
+            validator
+                .required("title", title)
+                .max_length("title", title, MAX_TITLE_LENGTH);
+        }
+        if let Some(Some(body)) = &self.body {
+            validator
+                .required("body", body)
+                .max_length("body", body, MAX_BODY_LENGTH);
+        }
+        if let Some(Some(_)) = &self.image {
+            validator.add("image", "read_only", "image can only be cleared with null, upload a new one instead.");
+        }
+
+        validator.finish()
+    }
+}
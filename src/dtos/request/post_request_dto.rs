@@ -1,8 +1,28 @@
 use serde::Deserialize;
 
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+use crate::utils::validation_util::Validator;
+
+const MAX_TITLE_LENGTH: usize = 200;
+const MAX_BODY_LENGTH: usize = 50_000;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PostRequestDto {
+    #[serde(default)]
     pub title: String,
+    #[serde(default)]
     pub body: String,
-}
\ No newline at end of file
+}
+
+impl Validate for PostRequestDto {
+    fn validate(&self) -> Result<(), Error> {
+        Validator::new()
+            .required("title", &self.title)
+            .max_length("title", &self.title, MAX_TITLE_LENGTH)
+            .required("body", &self.body)
+            .max_length("body", &self.body, MAX_BODY_LENGTH)
+            .finish()
+    }
+}
@@ -1,7 +1,20 @@
 use serde::Deserialize;
 
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+use crate::utils::validation_util::Validator;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenRefreshRequestDto {
+    #[serde(default)]
     pub refresh_token: String,
-}
\ No newline at end of file
+}
+
+impl Validate for TokenRefreshRequestDto {
+    fn validate(&self) -> Result<(), Error> {
+        Validator::new()
+            .required("refreshToken", &self.refresh_token)
+            .finish()
+    }
+}
@@ -0,0 +1,27 @@
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+use crate::utils::validation_util::Validator;
+
+const MAX_USERNAME_LENGTH: usize = 50;
+const MAX_PASSWORD_LENGTH: usize = 128;
+
+/// A user as written by the `user` administration commands, with the password in clear.
+#[derive(Debug)]
+pub struct UserRequestDto {
+    pub email: String,
+    pub username: String,
+    pub password: String,
+}
+
+impl Validate for UserRequestDto {
+    fn validate(&self) -> Result<(), Error> {
+        Validator::new()
+            .required("email", &self.email)
+            .email("email", &self.email)
+            .required("username", &self.username)
+            .max_length("username", &self.username, MAX_USERNAME_LENGTH)
+            .required("password", &self.password)
+            .max_length("password", &self.password, MAX_PASSWORD_LENGTH)
+            .finish()
+    }
+}
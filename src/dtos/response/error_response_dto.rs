@@ -1,7 +1,35 @@
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderValue, StatusCode};
+use axum::Json;
+use axum::response::{IntoResponse, Response};
 use serde::Serialize;
 
+use crate::dtos::response::field_error_dto::FieldErrorDto;
+
+/// RFC 7807 problem details, extended with a stable error code, the request id
+/// and per-field validation errors.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ErrorResponseDto {
-    pub error: String,
-}
\ No newline at end of file
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldErrorDto>,
+}
+
+impl IntoResponse for ErrorResponseDto {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        let mut response = (status, Json(self)).into_response();
+        response.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static("application/problem+json"));
+
+        response
+    }
+}
@@ -0,0 +1,13 @@
+use serde::Serialize;
+use chrono::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportResponseDto {
+    pub id: String,
+    pub status: String,
+    pub download_url: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
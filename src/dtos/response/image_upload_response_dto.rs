@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageUploadResponseDto {
+    pub key: String,
+    pub url: String,
+    pub expires_in: u32,
+}
@@ -1,4 +1,11 @@
 pub mod comment_response_dto;
 pub mod error_response_dto;
+pub mod export_response_dto;
+pub mod field_error_dto;
+pub mod health_response_dto;
+pub mod image_upload_response_dto;
+pub mod image_variant_dto;
+pub mod post_media_response_dto;
 pub mod post_response_dto;
 pub mod tokens_response_dto;
+pub mod user_response_dto;
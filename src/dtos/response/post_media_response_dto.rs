@@ -0,0 +1,18 @@
+use serde::Serialize;
+use chrono::prelude::*;
+
+use crate::dtos::response::image_variant_dto::ImageVariantDto;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostMediaResponseDto {
+    pub id: String,
+    pub url: String,
+    pub srcset: Vec<ImageVariantDto>,
+    pub webp_srcset: Vec<ImageVariantDto>,
+    pub position: i32,
+    pub alt_text: String,
+    pub caption: String,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
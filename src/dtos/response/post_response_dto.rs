@@ -1,6 +1,9 @@
 use serde::Serialize;
 use chrono::prelude::*;
 
+use crate::dtos::response::image_variant_dto::ImageVariantDto;
+use crate::dtos::response::post_media_response_dto::PostMediaResponseDto;
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PostResponseDto {
@@ -8,6 +11,9 @@ pub struct PostResponseDto {
     pub title: String,
     pub body: String,
     pub image_url: String,
+    pub image_srcset: Vec<ImageVariantDto>,
+    pub image_webp_srcset: Vec<ImageVariantDto>,
+    pub media: Vec<PostMediaResponseDto>,
     pub user_id: String,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
@@ -0,0 +1,13 @@
+use serde::Serialize;
+use chrono::prelude::*;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserResponseDto {
+    pub id: String,
+    pub email: String,
+    pub username: String,
+    pub role: String,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
@@ -1,4 +1,11 @@
+use std::fmt::{self, Display};
+
 use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use s3::error::S3Error;
+
+use crate::mappers::error_mapper::from_error_to_dto;
+use crate::models::field_error::FieldError;
 
 #[derive(Debug)]
 pub enum Error {
@@ -6,27 +13,146 @@ pub enum Error {
   Unauthorized(String),
   Forbidden(String),
   NotFound(String),
+  Conflict(String),
+  Gone(String),
+  PreconditionFailed(String),
+  PayloadTooLarge(String),
+  UnprocessableEntity(String),
+  Validation(Vec<FieldError>),
+  ServiceUnavailable(String),
   InternalServerError(String),
 }
 
 impl Error {
+  /// Logs the underlying cause, which clients never see, and returns a 500 with `message`.
+  pub fn internal(message: &str, cause: impl Display) -> Self {
+    tracing::error!(error = %cause, "{}", message);
+
+    Error::InternalServerError(message.to_string())
+  }
+
   pub fn code(&self) -> StatusCode {
     match self  {
       Error::BadRequest(_) => StatusCode::BAD_REQUEST,
       Error::Unauthorized(_) => StatusCode::UNAUTHORIZED,
       Error::Forbidden(_) => StatusCode::FORBIDDEN,
       Error::NotFound(_) => StatusCode::NOT_FOUND,
+      Error::Conflict(_) => StatusCode::CONFLICT,
+      Error::Gone(_) => StatusCode::GONE,
+      Error::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+      Error::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+      Error::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+      Error::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+      Error::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
       Error::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR
     }
   }
 
+  /// Machine-readable identifier of the error kind. Clients may rely on these
+  /// values, so they must never change.
+  pub fn error_code(&self) -> &'static str {
+    match self  {
+      Error::BadRequest(_) => "bad_request",
+      Error::Unauthorized(_) => "unauthorized",
+      Error::Forbidden(_) => "forbidden",
+      Error::NotFound(_) => "not_found",
+      Error::Conflict(_) => "conflict",
+      Error::Gone(_) => "gone",
+      Error::PreconditionFailed(_) => "precondition_failed",
+      Error::PayloadTooLarge(_) => "payload_too_large",
+      Error::UnprocessableEntity(_) => "unprocessable_entity",
+      Error::Validation(_) => "validation_failed",
+      Error::ServiceUnavailable(_) => "service_unavailable",
+      Error::InternalServerError(_) => "internal_error"
+    }
+  }
+
   pub fn message(&self) -> String {
     match self  {
       Error::BadRequest(v) => v.clone(),
       Error::Unauthorized(v) => v.clone(),
       Error::Forbidden(v) => v.clone(),
       Error::NotFound(v) => v.clone(),
+      Error::Conflict(v) => v.clone(),
+      Error::Gone(v) => v.clone(),
+      Error::PreconditionFailed(v) => v.clone(),
+      Error::PayloadTooLarge(v) => v.clone(),
+      Error::UnprocessableEntity(v) => v.clone(),
+      Error::Validation(_) => "Request validation failed.".to_string(),
+      Error::ServiceUnavailable(v) => v.clone(),
       Error::InternalServerError(v) => v.clone()
     }
   }
-}
\ No newline at end of file
+
+  pub fn field_errors(&self) -> &[FieldError] {
+    match self  {
+      Error::Validation(errors) => errors,
+      _ => &[],
+    }
+  }
+}
+
+impl Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", self.error_code(), self.message())
+  }
+}
+
+/// Renders the error as an `application/problem+json` response. Server errors were already logged
+/// with their cause where they were created, so this only logs at debug.
+impl IntoResponse for Error {
+  fn into_response(self) -> Response {
+    tracing::debug!(code = self.error_code(), "{}", self.message());
+
+    from_error_to_dto(&self).into_response()
+  }
+}
+
+impl From<sqlx::Error> for Error {
+  fn from(error: sqlx::Error) -> Self {
+    match error {
+      sqlx::Error::RowNotFound => Error::NotFound("Not found.".to_string()),
+      sqlx::Error::Database(error) if error.is_unique_violation() => {
+        tracing::debug!(error = %error, "Unique violation");
+        Error::Conflict("Resource already exists.".to_string())
+      }
+      sqlx::Error::Database(error) if error.is_foreign_key_violation() => {
+        tracing::debug!(error = %error, "Foreign key violation");
+        Error::UnprocessableEntity("Referenced resource does not exist.".to_string())
+      }
+      sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => {
+        tracing::error!(error = %error, "Database is unavailable");
+        Error::ServiceUnavailable("Database is unavailable.".to_string())
+      }
+      error => Error::internal("Something went wrong.", format!("Database error: {}", error)),
+    }
+  }
+}
+
+impl From<redis::RedisError> for Error {
+  fn from(error: redis::RedisError) -> Self {
+    if error.is_connection_dropped() || error.is_connection_refusal() || error.is_timeout() || error.is_io_error() {
+      tracing::error!(error = %error, "Cache is unavailable");
+      return Error::ServiceUnavailable("Cache is unavailable.".to_string());
+    }
+
+    Error::internal("Something went wrong.", format!("Redis error: {}", error))
+  }
+}
+
+impl From<S3Error> for Error {
+  fn from(error: S3Error) -> Self {
+    match error {
+      S3Error::Http(404, _) => Error::NotFound("File not found.".to_string()),
+      S3Error::Http(403, ref body) => {
+        tracing::warn!(body = %body, "Storage denied access");
+        Error::Forbidden("Access to the file was denied.".to_string())
+      }
+      S3Error::Http(status, ref body) if status >= 500 => {
+        tracing::error!(status, body = %body, "Storage is unavailable");
+        Error::ServiceUnavailable("Storage is unavailable.".to_string())
+      }
+      error => Error::internal("Something went wrong.", format!("Storage error: {}", error)),
+    }
+  }
+}
@@ -0,0 +1,16 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportStatus {
+  Pending,
+  Ready,
+  Failed,
+}
+
+impl ExportStatus {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ExportStatus::Pending => "pending",
+      ExportStatus::Ready => "ready",
+      ExportStatus::Failed => "failed",
+    }
+  }
+}
@@ -0,0 +1,17 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+  Up,
+  /// A non-critical dependency is down, the service can still serve traffic.
+  Degraded,
+  Down,
+}
+
+impl HealthStatus {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      HealthStatus::Up => "up",
+      HealthStatus::Degraded => "degraded",
+      HealthStatus::Down => "down",
+    }
+  }
+}
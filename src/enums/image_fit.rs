@@ -0,0 +1,25 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFit {
+  Contain,
+  Cover,
+  Fill,
+}
+
+impl ImageFit {
+  pub fn parse(value: &str) -> Option<Self> {
+    match value {
+      "contain" => Some(ImageFit::Contain),
+      "cover" => Some(ImageFit::Cover),
+      "fill" => Some(ImageFit::Fill),
+      _ => None,
+    }
+  }
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ImageFit::Contain => "contain",
+      ImageFit::Cover => "cover",
+      ImageFit::Fill => "fill",
+    }
+  }
+}
@@ -0,0 +1,24 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+  /// One JSON object per line, for log collectors.
+  Json,
+  /// Human readable lines, for local development.
+  Pretty,
+}
+
+impl FromStr for LogFormat {
+  type Err = String;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    match value {
+      "json" => Ok(LogFormat::Json),
+      "pretty" => Ok(LogFormat::Pretty),
+      _ => Err(format!("Unknown log format {}.", value)),
+    }
+  }
+}
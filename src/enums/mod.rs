@@ -1 +1,7 @@
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod export_status;
+pub mod health_status;
+pub mod image_fit;
+pub mod log_format;
+pub mod store_backend;
+pub mod user_role;
@@ -0,0 +1,24 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreBackend {
+  S3,
+  Local,
+  Memory,
+}
+
+impl FromStr for StoreBackend {
+  type Err = String;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    match value {
+      "s3" => Ok(StoreBackend::S3),
+      "local" => Ok(StoreBackend::Local),
+      "memory" => Ok(StoreBackend::Memory),
+      _ => Err(format!("Unknown store backend {}.", value)),
+    }
+  }
+}
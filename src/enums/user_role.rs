@@ -0,0 +1,16 @@
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum UserRole {
+  User,
+  Admin,
+}
+
+impl UserRole {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      UserRole::User => "user",
+      UserRole::Admin => "admin",
+    }
+  }
+}
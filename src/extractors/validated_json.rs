@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use axum::extract::{FromRequest, Request};
+use axum::extract::rejection::JsonRejection;
+use axum::Json;
+use serde::de::DeserializeOwned;
+
+use crate::enums::error::Error;
+use crate::traits::validate::Validate;
+
+/// Like `Json`, but runs `Validate` on the body and turns every failure into a
+/// problem response: malformed JSON is a 400, invalid content a 422.
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+  where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+  type Rejection = Error;
+
+  async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+    let Json(value) = Json::<T>::from_request(request, state)
+      .await
+      .map_err(|rejection| match rejection {
+        JsonRejection::JsonDataError(error) => Error::UnprocessableEntity(error.body_text()),
+        rejection => Error::BadRequest(rejection.body_text()),
+      })?;
+
+    value.validate()?;
+
+    Ok(ValidatedJson(value))
+  }
+}
@@ -1,13 +1,14 @@
 use std::sync::Arc;
 
-use axum::{Extension, Json};
-use axum::http::{HeaderMap, StatusCode};
+use axum::Extension;
+use axum::http::HeaderMap;
 use axum::http::header::AUTHORIZATION;
 use axum::response::IntoResponse;
 
 use crate::dtos::request::login_request_dto::LoginRequestDto;
 use crate::dtos::request::token_refresh_request_dto::TokenRefreshRequestDto;
-use crate::dtos::response::error_response_dto::ErrorResponseDto;
+use crate::enums::error::Error;
+use crate::extractors::validated_json::ValidatedJson;
 use crate::mappers::auth_mapper::from_login_dto_to_user;
 use crate::services::auth_service::AuthService;
 use crate::traits::from_result_to_response::FromResultToResponse;
@@ -19,8 +20,8 @@ pub struct AuthHandler;
 impl AuthHandler {
   pub async fn login(
     Extension(service): Extension<Arc<dyn AuthService>>,
-    Json(body): Json<LoginRequestDto>
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
+    ValidatedJson(body): ValidatedJson<LoginRequestDto>
+  ) -> Result<impl IntoResponse, Error> {
     let user = map_body_to_model(body, from_login_dto_to_user).await;
 
     service.login(user)
@@ -31,7 +32,7 @@ impl AuthHandler {
   pub async fn logout(
     Extension(service): Extension<Arc<dyn AuthService>>,
     headers: HeaderMap
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
+  ) -> Result<impl IntoResponse, Error> {
     let authorization_header = headers.get(AUTHORIZATION).unwrap().to_str().unwrap().to_string();
 
     service.logout(authorization_header)
@@ -41,8 +42,8 @@ impl AuthHandler {
 
   pub async fn refresh_token(
     Extension(service): Extension<Arc<dyn AuthService>>,
-    Json(body): Json<TokenRefreshRequestDto>
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
+    ValidatedJson(body): ValidatedJson<TokenRefreshRequestDto>
+  ) -> Result<impl IntoResponse, Error> {
     service.refresh(body.refresh_token)
       .await
       .to_response(|tokens| tokens)
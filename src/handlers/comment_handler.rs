@@ -1,54 +1,78 @@
 use std::sync::Arc;
 
-use axum::{Extension, Json};
+use axum::Extension;
 use axum::extract::Path;
-use axum::http::StatusCode;
+use axum::http::HeaderMap;
+use axum::http::header::{IF_MATCH, IF_NONE_MATCH};
 use axum::response::IntoResponse;
 use uuid::Uuid;
 
+use crate::dtos::request::comment_patch_request_dto::CommentPatchRequestDto;
 use crate::dtos::request::comment_request_dto::CommentRequestDto;
-use crate::dtos::response::error_response_dto::ErrorResponseDto;
-use crate::mappers::comment_mapper::{from_comment_to_dto, from_dto_to_comment};
+use crate::enums::error::Error;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::mappers::comment_mapper::{from_comment_to_dto, from_dto_to_comment, from_dto_to_comment_patch};
 use crate::models::token_claims::TokenClaims;
 use crate::services::comment_service::CommentService;
-use crate::traits::from_result_to_response::FromResultToResponse;
+use crate::traits::from_result_to_created_response::FromResultToCreatedResponse;
+use crate::traits::from_result_to_tagged_response::FromResultToTaggedResponse;
 use crate::utils::mapper_util::from_model_to_dto_list;
+use crate::utils::request_util::get_header;
 
 pub struct CommentHandler;
 
 impl CommentHandler {
   pub async fn get_all(
     Path(post_id): Path<Uuid>,
-    Extension(service): Extension<Arc<dyn CommentService>>
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
+    Extension(service): Extension<Arc<dyn CommentService>>,
+    headers: HeaderMap,
+  ) -> Result<impl IntoResponse, Error> {
     service.get_all_for_post(post_id)
       .await
-      .to_response(|comments| from_model_to_dto_list(comments, from_comment_to_dto))
+      .to_tagged_response(get_header(&headers, IF_NONE_MATCH), |comments| from_model_to_dto_list(comments, from_comment_to_dto))
   }
 
   pub async fn create(
     Extension(service): Extension<Arc<dyn CommentService>>,
     Extension(claims): Extension<Arc<TokenClaims>>,
-    Json(body): Json<CommentRequestDto>,
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
+    ValidatedJson(body): ValidatedJson<CommentRequestDto>,
+  ) -> Result<impl IntoResponse, Error> {
     let mut comment = from_dto_to_comment(&body);
-    comment.user_id = claims.sub.clone();
+    comment.user_id = claims.sub;
 
     service.create(comment)
       .await
-      .to_response(|comment| from_comment_to_dto(&comment))
+      .to_created_response(
+        |comment| format!("/api/posts/{}/comments/{}", comment.post_id, comment.id),
+        |comment| from_comment_to_dto(&comment),
+      )
   }
 
   pub async fn update(
-    Path((_, id)): Path<(Uuid, Uuid)>,
+    Path((post_id, id)): Path<(Uuid, Uuid)>,
     Extension(service): Extension<Arc<dyn CommentService>>,
-    Json(body): Json<CommentRequestDto>,
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<CommentRequestDto>,
+  ) -> Result<impl IntoResponse, Error> {
     let mut comment = from_dto_to_comment(&body);
     comment.id = id;
+    comment.post_id = post_id;
 
-    service.update(comment)
+    service.update(&claims, comment, get_header(&headers, IF_MATCH))
+      .await
+      .to_tagged_response(None, |comment| from_comment_to_dto(&comment))
+  }
+
+  pub async fn patch(
+    Path((post_id, id)): Path<(Uuid, Uuid)>,
+    Extension(service): Extension<Arc<dyn CommentService>>,
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<CommentPatchRequestDto>,
+  ) -> Result<impl IntoResponse, Error> {
+    service.patch(&claims, post_id, id, from_dto_to_comment_patch(&body), get_header(&headers, IF_MATCH))
       .await
-      .to_response(|comment| from_comment_to_dto(&comment))
+      .to_tagged_response(None, |comment| from_comment_to_dto(&comment))
   }
 }
\ No newline at end of file
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use axum::Extension;
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, StatusCode};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::response::IntoResponse;
+use uuid::Uuid;
+
+use crate::dtos::request::export_download_request_dto::ExportDownloadRequestDto;
+use crate::enums::error::Error;
+use crate::mappers::export_mapper::from_export_to_dto;
+use crate::models::token_claims::TokenClaims;
+use crate::services::export_service::ExportService;
+use crate::traits::from_result_to_response::FromResultToResponse;
+
+pub struct ExportHandler;
+
+impl ExportHandler {
+  pub async fn create(
+    Extension(service): Extension<Arc<dyn ExportService>>,
+    Extension(claims): Extension<Arc<TokenClaims>>,
+  ) -> Result<impl IntoResponse, Error> {
+    service.request(claims.sub)
+      .await
+      .to_response_with_status(StatusCode::ACCEPTED, |export| from_export_to_dto(&export))
+  }
+
+  pub async fn get_one(
+    Path(id): Path<Uuid>,
+    Extension(service): Extension<Arc<dyn ExportService>>,
+    Extension(claims): Extension<Arc<TokenClaims>>,
+  ) -> Result<impl IntoResponse, Error> {
+    service.get_one(claims.sub, id)
+      .await
+      .to_response(|export| from_export_to_dto(&export))
+  }
+
+  pub async fn download(
+    Path(id): Path<Uuid>,
+    Query(query): Query<ExportDownloadRequestDto>,
+    Extension(service): Extension<Arc<dyn ExportService>>,
+  ) -> Result<impl IntoResponse, Error> {
+    let content = service.download(id, query.token).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, "application/zip".parse().unwrap());
+    headers.insert(
+      CONTENT_DISPOSITION,
+      format!("attachment; filename=\"rustblog-export-{}.zip\"", id).parse().unwrap(),
+    );
+
+    Ok((headers, content))
+  }
+}
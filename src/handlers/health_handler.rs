@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use axum::Extension;
+use axum::http::StatusCode;
+use axum::Json;
+use axum::response::IntoResponse;
+
+use crate::enums::health_status::HealthStatus;
+use crate::mappers::health_mapper::from_health_report_to_dto;
+use crate::models::health_report::HealthReport;
+use crate::services::health_service::HealthService;
+
+pub struct HealthHandler;
+
+impl HealthHandler {
+  /// The process is up and serving HTTP, dependencies are not checked.
+  pub async fn live() -> impl IntoResponse {
+    let report = HealthReport { status: HealthStatus::Up, dependencies: Vec::new() };
+
+    Json(from_health_report_to_dto(&report))
+  }
+
+  pub async fn ready(
+    Extension(service): Extension<Arc<dyn HealthService>>,
+  ) -> impl IntoResponse {
+    let report = service.ready().await;
+    let status = match report.status {
+      HealthStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+      _ => StatusCode::OK,
+    };
+
+    (status, Json(from_health_report_to_dto(&report)))
+  }
+}
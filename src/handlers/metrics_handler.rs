@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use axum::Extension;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+
+use crate::enums::error::Error;
+use crate::services::metrics_service::MetricsService;
+
+// Content type of the Prometheus text exposition format.
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub struct MetricsHandler;
+
+impl MetricsHandler {
+  pub async fn get(
+    Extension(service): Extension<Arc<dyn MetricsService>>,
+  ) -> Result<impl IntoResponse, Error> {
+    let body = service.render().await?;
+
+    Ok(([(CONTENT_TYPE, METRICS_CONTENT_TYPE)], body))
+  }
+}
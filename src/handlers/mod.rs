@@ -1,4 +1,7 @@
 pub mod auth_handler;
 pub mod comment_handler;
+pub mod export_handler;
+pub mod health_handler;
+pub mod metrics_handler;
 pub mod post_handler;
-pub mod proxy_handler;
\ No newline at end of file
+pub mod proxy_handler;
@@ -1,82 +1,131 @@
 use std::sync::Arc;
 
-use axum::{Extension, Json};
+use axum::Extension;
 use axum::extract::multipart::Multipart;
 use axum::extract::Path;
-use axum::http::StatusCode;
+use axum::http::HeaderMap;
+use axum::http::header::{IF_MATCH, IF_NONE_MATCH};
 use axum::response::IntoResponse;
 use uuid::Uuid;
 
+use crate::config::settings::Settings;
+use crate::dtos::request::image_confirm_request_dto::ImageConfirmRequestDto;
+use crate::dtos::request::image_upload_request_dto::ImageUploadRequestDto;
+use crate::dtos::request::post_media_order_request_dto::PostMediaOrderRequestDto;
+use crate::dtos::request::post_media_request_dto::PostMediaRequestDto;
+use crate::dtos::request::post_patch_request_dto::PostPatchRequestDto;
 use crate::dtos::request::post_request_dto::PostRequestDto;
-use crate::dtos::response::error_response_dto::ErrorResponseDto;
-use crate::mappers::post_mapper::{from_dto_to_post, from_post_to_dto};
+use crate::enums::error::Error;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::mappers::image_upload_mapper::from_image_upload_to_dto;
+use crate::mappers::post_mapper::{from_dto_to_post, from_dto_to_post_patch, from_post_to_dto};
+use crate::mappers::post_media_mapper::{from_dto_to_post_media, from_post_media_to_dto};
 use crate::models::token_claims::TokenClaims;
 use crate::services::post_service::PostService;
+use crate::traits::from_result_to_created_response::FromResultToCreatedResponse;
 use crate::traits::from_result_to_response::FromResultToResponse;
 use crate::traits::from_result_to_response_with_no_content::FromResultToResponseWithNoContent;
+use crate::traits::from_result_to_tagged_response::FromResultToTaggedResponse;
 use crate::utils::file_util::get_file_from_multipart;
 use crate::utils::mapper_util::from_model_to_dto_list;
+use crate::utils::request_util::get_header;
 
 pub struct PostHandler;
 
 impl PostHandler {
   pub async fn get_all(
-    Extension(service): Extension<Arc<dyn PostService>>
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
+    Extension(service): Extension<Arc<dyn PostService>>,
+    headers: HeaderMap,
+  ) -> Result<impl IntoResponse, Error> {
     service.get_all()
       .await
-      .to_response(|posts| from_model_to_dto_list(posts, from_post_to_dto))
+      .to_tagged_response(get_header(&headers, IF_NONE_MATCH), |posts| from_model_to_dto_list(posts, from_post_to_dto))
   }
 
   pub async fn get_one(
     Path(id): Path<Uuid>,
     Extension(service): Extension<Arc<dyn PostService>>,
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
+    headers: HeaderMap,
+  ) -> Result<impl IntoResponse, Error> {
     service.get_one(id)
       .await
-      .to_response(|post| from_post_to_dto(&post))
+      .to_tagged_response(get_header(&headers, IF_NONE_MATCH), |post| from_post_to_dto(&post))
   }
 
   pub async fn create(
     Extension(service): Extension<Arc<dyn PostService>>,
     Extension(claims): Extension<Arc<TokenClaims>>,
-    Json(body): Json<PostRequestDto>,
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
+    ValidatedJson(body): ValidatedJson<PostRequestDto>,
+  ) -> Result<impl IntoResponse, Error> {
     let mut post = from_dto_to_post(&body);
-    post.user_id = claims.sub.clone();
+    post.user_id = claims.sub;
 
     service.create(post)
       .await
-      .to_response(|post| from_post_to_dto(&post))
+      .to_created_response(|post| format!("/api/posts/{}", post.id), |post| from_post_to_dto(&post))
   }
 
   pub async fn update(
     Path(id): Path<Uuid>,
     Extension(service): Extension<Arc<dyn PostService>>,
-    Json(body): Json<PostRequestDto>,
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<PostRequestDto>,
+  ) -> Result<impl IntoResponse, Error> {
     let mut post = from_dto_to_post(&body);
     post.id = id;
 
-    service.update(post)
+    service.update(&claims, post, get_header(&headers, IF_MATCH))
       .await
-      .to_response(|post| from_post_to_dto(&post))
+      .to_tagged_response(None, |post| from_post_to_dto(&post))
+  }
+
+  pub async fn patch(
+    Path(id): Path<Uuid>,
+    Extension(service): Extension<Arc<dyn PostService>>,
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<PostPatchRequestDto>,
+  ) -> Result<impl IntoResponse, Error> {
+    service.patch(&claims, id, from_dto_to_post_patch(&body), get_header(&headers, IF_MATCH))
+      .await
+      .to_tagged_response(None, |post| from_post_to_dto(&post))
   }
 
   pub async fn add_image(
     Path(id): Path<Uuid>,
     Extension(service): Extension<Arc<dyn PostService>>,
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    Extension(settings): Extension<Arc<Settings>>,
     multipart: Multipart,
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
-    let result = get_file_from_multipart("file".to_string(), multipart).await;
-    if result.is_err() {
-      let error = result.unwrap_err();
-      return Err((error.code(), Json(ErrorResponseDto { error: error.message() })));
-    }
+  ) -> Result<impl IntoResponse, Error> {
+    let content = get_file_from_multipart("file".to_string(), multipart, settings.max_upload_size).await?;
+
+    service.add_image(&claims, id, content)
+      .await
+      .to_response(|post| from_post_to_dto(&post))
+  }
 
-    let (content, content_type) = result.unwrap();
+  pub async fn create_image_upload(
+    Path(id): Path<Uuid>,
+    Extension(service): Extension<Arc<dyn PostService>>,
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    Extension(settings): Extension<Arc<Settings>>,
+    ValidatedJson(body): ValidatedJson<ImageUploadRequestDto>,
+  ) -> Result<impl IntoResponse, Error> {
+    service.create_image_upload(&claims, id, body.content_type, body.content_length, settings.max_upload_size)
+      .await
+      .to_response(|upload| from_image_upload_to_dto(&upload))
+  }
 
-    service.add_image(id, content, content_type)
+  pub async fn confirm_image_upload(
+    Path(id): Path<Uuid>,
+    Extension(service): Extension<Arc<dyn PostService>>,
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    Extension(settings): Extension<Arc<Settings>>,
+    ValidatedJson(body): ValidatedJson<ImageConfirmRequestDto>,
+  ) -> Result<impl IntoResponse, Error> {
+    service.confirm_image_upload(&claims, id, body.key, settings.max_upload_size)
       .await
       .to_response(|post| from_post_to_dto(&post))
   }
@@ -84,8 +133,76 @@ impl PostHandler {
   pub async fn delete(
     Path(id): Path<Uuid>,
     Extension(service): Extension<Arc<dyn PostService>>,
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
-    service.delete(id)
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    headers: HeaderMap,
+  ) -> Result<impl IntoResponse, Error> {
+    service.delete(&claims, id, get_header(&headers, IF_MATCH))
+      .await
+      .to_response_with_no_content()
+  }
+
+  pub async fn get_media(
+    Path(id): Path<Uuid>,
+    Extension(service): Extension<Arc<dyn PostService>>,
+  ) -> Result<impl IntoResponse, Error> {
+    service.get_media(id)
+      .await
+      .to_response(|media| from_model_to_dto_list(media, from_post_media_to_dto))
+  }
+
+  pub async fn add_media(
+    Path(id): Path<Uuid>,
+    Extension(service): Extension<Arc<dyn PostService>>,
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    Extension(settings): Extension<Arc<Settings>>,
+    headers: HeaderMap,
+    multipart: Multipart,
+  ) -> Result<impl IntoResponse, Error> {
+    let content = get_file_from_multipart("file".to_string(), multipart, settings.max_upload_size).await?;
+
+    service.add_media(&claims, id, content, get_header(&headers, IF_MATCH))
+      .await
+      .to_created_response(
+        |media| format!("/api/posts/{}/media/{}", media.post_id, media.id),
+        |media| from_post_media_to_dto(&media),
+      )
+  }
+
+  pub async fn update_media(
+    Path((id, media_id)): Path<(Uuid, Uuid)>,
+    Extension(service): Extension<Arc<dyn PostService>>,
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<PostMediaRequestDto>,
+  ) -> Result<impl IntoResponse, Error> {
+    let mut media = from_dto_to_post_media(&body);
+    media.id = media_id;
+    media.post_id = id;
+
+    service.update_media(&claims, media, get_header(&headers, IF_MATCH))
+      .await
+      .to_response(|media| from_post_media_to_dto(&media))
+  }
+
+  pub async fn reorder_media(
+    Path(id): Path<Uuid>,
+    Extension(service): Extension<Arc<dyn PostService>>,
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<PostMediaOrderRequestDto>,
+  ) -> Result<impl IntoResponse, Error> {
+    service.reorder_media(&claims, id, body.ids, get_header(&headers, IF_MATCH))
+      .await
+      .to_response(|media| from_model_to_dto_list(media, from_post_media_to_dto))
+  }
+
+  pub async fn delete_media(
+    Path((id, media_id)): Path<(Uuid, Uuid)>,
+    Extension(service): Extension<Arc<dyn PostService>>,
+    Extension(claims): Extension<Arc<TokenClaims>>,
+    headers: HeaderMap,
+  ) -> Result<impl IntoResponse, Error> {
+    service.delete_media(&claims, id, media_id, get_header(&headers, IF_MATCH))
       .await
       .to_response_with_no_content()
   }
@@ -1,14 +1,29 @@
 use std::sync::Arc;
 
-use axum::{Extension, Json};
-use axum::extract::Path;
-use axum::http::{HeaderMap, StatusCode};
-use axum::response::IntoResponse;
-use reqwest;
-use s3::utils::GetAndConvertHeaders;
+use axum::Extension;
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::http::header::{
+  ACCEPT_RANGES, CACHE_CONTROL, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG, IF_NONE_MATCH, LAST_MODIFIED, RANGE,
+  X_CONTENT_TYPE_OPTIONS,
+};
+use axum::response::{IntoResponse, Response};
 
-use crate::config::settings::Settings;
-use crate::dtos::response::error_response_dto::ErrorResponseDto;
+use crate::dtos::request::image_transform_request_dto::ImageTransformRequestDto;
+use crate::enums::error::Error;
+use crate::services::image_service::ImageService;
+use crate::services::store_service::StoreService;
+use crate::utils::http_util::{ByteRange, etag_matches, parse_range};
+use crate::utils::store_key_util::is_private_key;
+
+// Object keys are never reused for different content, so they can be cached forever.
+const CACHE_CONTROL_VALUE: &str = "public, max-age=31536000, immutable";
+
+fn insert_header(headers: &mut HeaderMap, name: axum::http::HeaderName, value: &str) {
+  if let Ok(value) = HeaderValue::from_str(value) {
+    headers.insert(name, value);
+  }
+}
 
 #[derive(Clone)]
 pub struct ProxyHandler;
@@ -16,16 +31,67 @@ pub struct ProxyHandler;
 impl ProxyHandler {
   pub async fn serve_public_bucket(
     Path(id): Path<String>,
-    Extension(settings): Extension<Arc<Settings>>,
-  ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponseDto>)> {
-    let base_url = settings.minio_public_addr.as_str();
-    let bucket = settings.minio_bucket_name.as_str();
+    Query(query): Query<ImageTransformRequestDto>,
+    request_headers: HeaderMap,
+    Extension(store_service): Extension<Arc<dyn StoreService>>,
+    Extension(image_service): Extension<Arc<dyn ImageService>>,
+  ) -> Result<Response, Error> {
+    if is_private_key(id.as_str()) {
+      return Err(Error::NotFound("File not found.".to_string()));
+    }
 
-    let response = reqwest::get(format!("{base_url}/{bucket}/{id}")).await.unwrap();
+    let key = if query.is_empty() {
+      id
+    } else {
+      image_service.get_transformed(id, query).await?
+    };
+
+    let object = store_service.head(key).await?;
 
     let mut headers = HeaderMap::new();
-    headers.insert("content-type", response.headers().get_string("content-type").unwrap().parse().unwrap());
+    insert_header(&mut headers, CONTENT_TYPE, object.content_type.as_str());
+    insert_header(&mut headers, CACHE_CONTROL, CACHE_CONTROL_VALUE);
+    insert_header(&mut headers, ACCEPT_RANGES, "bytes");
+    // Browsers must not guess a type other than the sniffed image type the object was stored with.
+    insert_header(&mut headers, X_CONTENT_TYPE_OPTIONS, "nosniff");
+    if let Some(e_tag) = &object.e_tag {
+      insert_header(&mut headers, ETAG, e_tag.as_str());
+    }
+    if let Some(last_modified) = &object.last_modified {
+      insert_header(&mut headers, LAST_MODIFIED, last_modified.as_str());
+    }
+
+    let if_none_match = request_headers.get(IF_NONE_MATCH).and_then(|value| value.to_str().ok());
+    if let (Some(if_none_match), Some(e_tag)) = (if_none_match, &object.e_tag) {
+      if etag_matches(if_none_match, e_tag) {
+        return Ok((StatusCode::NOT_MODIFIED, headers).into_response());
+      }
+    }
+
+    let range = request_headers
+      .get(RANGE)
+      .and_then(|value| value.to_str().ok())
+      .map_or(ByteRange::Full, |value| parse_range(value, object.content_length));
+
+    match range {
+      ByteRange::Unsatisfiable => {
+        insert_header(&mut headers, CONTENT_RANGE, format!("bytes */{}", object.content_length).as_str());
+
+        Ok((StatusCode::RANGE_NOT_SATISFIABLE, headers).into_response())
+      }
+      ByteRange::Partial(start, end) => {
+        let body = store_service.stream(object.key, Some((start, end))).await?;
+        insert_header(&mut headers, CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, object.content_length).as_str());
+        insert_header(&mut headers, CONTENT_LENGTH, (end - start + 1).to_string().as_str());
+
+        Ok((StatusCode::PARTIAL_CONTENT, headers, body).into_response())
+      }
+      ByteRange::Full => {
+        let body = store_service.stream(object.key, None).await?;
+        insert_header(&mut headers, CONTENT_LENGTH, object.content_length.to_string().as_str());
 
-    return Ok((headers, response.bytes().await.unwrap()));
+        Ok((StatusCode::OK, headers, body).into_response())
+      }
+    }
   }
-}
\ No newline at end of file
+}
@@ -1,8 +1,10 @@
 extern crate core;
 
+pub mod commands;
 pub mod config;
 pub mod dtos;
 pub mod enums;
+pub mod extractors;
 pub mod handlers;
 pub mod mappers;
 pub mod middlewares;
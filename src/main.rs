@@ -1,16 +1,51 @@
+use std::process::ExitCode;
 use std::sync::Arc;
 
-use rust_blog::config::demo::init_demo;
-use rust_blog::container::Container;
-use rust_blog::create_app::create_app;
+use clap::Parser;
+
+use rust_blog::commands::admin_command::run_admin;
+use rust_blog::commands::cli::{Cli, Command};
+use rust_blog::commands::migrate_command::migrate;
+use rust_blog::commands::post_command::post;
+use rust_blog::commands::serve_command::serve;
+use rust_blog::commands::storage_command::storage;
+use rust_blog::commands::tokens_command::tokens;
+use rust_blog::commands::user_command::user;
+use rust_blog::config::logging::init_logging;
+use rust_blog::config::settings::{init_database_settings, init_settings};
+use rust_blog::config::telemetry::init_tracer_provider;
 
 #[tokio::main]
-async fn main() {
-  let container = Arc::new(Container::new().await);
+async fn main() -> ExitCode {
+  let cli = Cli::parse();
+
+  // Migrations only need the database, the other commands get the full settings checked.
+  let command = cli.command.unwrap_or(Command::Serve);
+  let settings = match command {
+    Command::Migrate { .. } => init_database_settings(),
+    _ => init_settings(),
+  };
+  let settings = match settings {
+    Ok(settings) => Arc::new(settings),
+    Err(err) => {
+      eprint!("{}", err);
+      return ExitCode::FAILURE;
+    }
+  };
 
-  let user_service = container.user_service.clone();
-  init_demo(user_service).await;
+  // Only the server exports traces, the other commands are short-lived.
+  let tracer_provider = match command {
+    Command::Serve => init_tracer_provider(&settings),
+    _ => None,
+  };
+  init_logging(&settings, tracer_provider.as_ref());
 
-  let listener = tokio::net::TcpListener::bind("0.0.0.0:8000").await.unwrap();
-  axum::serve(listener, create_app(container.clone())).await.unwrap()
-}
\ No newline at end of file
+  match command {
+    Command::Serve => serve(settings, tracer_provider).await,
+    Command::Migrate { action } => migrate(&settings, action).await,
+    Command::User { action } => run_admin(settings, async |container| user(container, action).await).await,
+    Command::Tokens { action } => run_admin(settings, async |container| tokens(container, action).await).await,
+    Command::Post { action } => run_admin(settings, async |container| post(container, action).await).await,
+    Command::Storage { action } => run_admin(settings, async |container| storage(container, action).await).await,
+  }
+}
@@ -7,6 +7,7 @@ pub fn from_login_dto_to_user(dto: LoginRequestDto) -> User {
         email: dto.email.clone(),
         username: Default::default(),
         password: dto.password.clone(),
+        role: Default::default(),
         created_at: Default::default(),
         updated_at: Default::default(),
     }
@@ -1,6 +1,8 @@
+use crate::dtos::request::comment_patch_request_dto::CommentPatchRequestDto;
 use crate::dtos::request::comment_request_dto::CommentRequestDto;
 use crate::dtos::response::comment_response_dto::CommentResponseDto;
 use crate::models::comment::Comment;
+use crate::models::comment_patch::CommentPatch;
 
 pub fn from_dto_to_comment(dto: &CommentRequestDto) -> Comment {
     Comment {
@@ -13,6 +15,12 @@ pub fn from_dto_to_comment(dto: &CommentRequestDto) -> Comment {
     }
 }
 
+pub fn from_dto_to_comment_patch(dto: &CommentPatchRequestDto) -> CommentPatch {
+    CommentPatch {
+        body: dto.body.clone().flatten(),
+    }
+}
+
 pub fn from_comment_to_dto(model: &Comment) -> CommentResponseDto {
     CommentResponseDto {
         id: model.id.to_string(),
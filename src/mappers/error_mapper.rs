@@ -0,0 +1,25 @@
+use crate::dtos::response::error_response_dto::ErrorResponseDto;
+use crate::dtos::response::field_error_dto::FieldErrorDto;
+use crate::enums::error::Error;
+use crate::middlewares::request_id_middleware::current_request_id;
+
+pub fn from_error_to_dto(error: &Error) -> ErrorResponseDto {
+    let status = error.code();
+
+    ErrorResponseDto {
+        problem_type: "about:blank".to_string(),
+        title: status.canonical_reason().unwrap_or_default().to_string(),
+        status: status.as_u16(),
+        detail: error.message(),
+        code: error.error_code().to_string(),
+        request_id: current_request_id(),
+        errors: error.field_errors()
+            .iter()
+            .map(|field_error| FieldErrorDto {
+                field: field_error.field.clone(),
+                code: field_error.code.clone(),
+                message: field_error.message.clone(),
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::Utc;
+
+use crate::dtos::response::export_response_dto::ExportResponseDto;
+use crate::enums::export_status::ExportStatus;
+use crate::models::export::Export;
+
+pub fn from_export_to_dto(model: &Export) -> ExportResponseDto {
+    let is_downloadable = model.status == ExportStatus::Ready.as_str()
+        && model.expires_at.is_some_and(|expires_at| expires_at > Utc::now());
+
+    let download_url = if is_downloadable {
+        Some(format!("/api/exports/{}/download?token={}", model.id, model.download_token))
+    } else {
+        None
+    };
+
+    ExportResponseDto {
+        id: model.id.to_string(),
+        status: model.status.clone(),
+        download_url,
+        expires_at: model.expires_at,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
@@ -0,0 +1,19 @@
+use crate::dtos::response::health_response_dto::{DependencyHealthDto, HealthResponseDto};
+use crate::models::health_report::{DependencyHealth, HealthReport};
+
+pub fn from_dependency_health_to_dto(model: &DependencyHealth) -> DependencyHealthDto {
+    DependencyHealthDto {
+        name: model.name.clone(),
+        status: model.status.as_str().to_string(),
+        critical: model.critical,
+        latency_ms: model.latency_ms,
+        error: model.error.clone(),
+    }
+}
+
+pub fn from_health_report_to_dto(model: &HealthReport) -> HealthResponseDto {
+    HealthResponseDto {
+        status: model.status.as_str().to_string(),
+        dependencies: model.dependencies.iter().map(from_dependency_health_to_dto).collect(),
+    }
+}
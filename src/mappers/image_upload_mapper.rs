@@ -0,0 +1,10 @@
+use crate::dtos::response::image_upload_response_dto::ImageUploadResponseDto;
+use crate::models::image_upload::ImageUpload;
+
+pub fn from_image_upload_to_dto(model: &ImageUpload) -> ImageUploadResponseDto {
+    ImageUploadResponseDto {
+        key: model.key.to_string(),
+        url: model.url.clone(),
+        expires_in: model.expires_in,
+    }
+}
@@ -1,3 +1,9 @@
 pub mod auth_mapper;
 pub mod comment_mapper;
-pub mod post_mapper;
\ No newline at end of file
+pub mod error_mapper;
+pub mod export_mapper;
+pub mod health_mapper;
+pub mod image_upload_mapper;
+pub mod post_mapper;
+pub mod post_media_mapper;
+pub mod user_mapper;
@@ -1,8 +1,13 @@
 use uuid::Uuid;
 
+use crate::dtos::request::post_patch_request_dto::PostPatchRequestDto;
 use crate::dtos::request::post_request_dto::PostRequestDto;
+use crate::dtos::response::image_variant_dto::ImageVariantDto;
 use crate::dtos::response::post_response_dto::PostResponseDto;
+use crate::mappers::post_media_mapper::from_post_media_to_dto;
 use crate::models::post::Post;
+use crate::models::post_patch::PostPatch;
+use crate::utils::image_util::{get_image_key, get_webp_key, has_webp_variants};
 
 pub fn from_dto_to_post(dto: &PostRequestDto) -> Post {
     Post {
@@ -10,15 +15,57 @@ pub fn from_dto_to_post(dto: &PostRequestDto) -> Post {
         title: dto.title.clone(),
         body: dto.body.clone(),
         image_id: Default::default(),
+        image_width: Default::default(),
+        image_widths: Default::default(),
+        image_content_type: Default::default(),
         user_id: Default::default(),
         created_at: Default::default(),
         updated_at: Default::default(),
+        media: Default::default(),
     }
 }
 
+pub fn from_dto_to_post_patch(dto: &PostPatchRequestDto) -> PostPatch {
+    PostPatch {
+        title: dto.title.clone().flatten(),
+        body: dto.body.clone().flatten(),
+        clear_image: matches!(dto.image, Some(None)),
+    }
+}
+
+pub fn to_srcset(image_id: Uuid, width: i32, widths: &[i32], get_key: fn(Uuid, Option<u32>) -> String) -> Vec<ImageVariantDto> {
+    if image_id == Uuid::nil() || width == 0 {
+        return Vec::new();
+    }
+
+    let mut srcset: Vec<ImageVariantDto> = widths
+        .iter()
+        .map(|width| ImageVariantDto {
+            url: format!("/assets/images/{}", get_key(image_id, Some(*width as u32))),
+            width: *width,
+        })
+        .collect();
+
+    srcset.push(ImageVariantDto {
+        url: format!("/assets/images/{}", get_key(image_id, None)),
+        width,
+    });
+
+    srcset
+}
+
+/// WebP renditions of an image, none for GIFs since they are kept as-is.
+pub fn to_webp_srcset(image_id: Uuid, width: i32, widths: &[i32], content_type: &str) -> Vec<ImageVariantDto> {
+    if !has_webp_variants(content_type) {
+        return Vec::new();
+    }
+
+    to_srcset(image_id, width, widths, get_webp_key)
+}
+
 pub fn from_post_to_dto(model: &Post) -> PostResponseDto {
     let image_url = if model.image_id != Uuid::nil() {
-        format!("/assets/images/{}", model.image_id.to_string())
+        format!("/assets/images/{}", model.image_id)
     } else {
         String::new()
     };
@@ -28,6 +75,11 @@ pub fn from_post_to_dto(model: &Post) -> PostResponseDto {
         title: model.title.clone(),
         body: model.body.clone(),
         image_url,
+        image_srcset: to_srcset(model.image_id, model.image_width, &model.image_widths, get_image_key),
+        image_webp_srcset: to_webp_srcset(
+            model.image_id, model.image_width, &model.image_widths, &model.image_content_type,
+        ),
+        media: model.media.iter().map(from_post_media_to_dto).collect(),
         user_id: model.user_id.to_string(),
         created_at: model.created_at,
         updated_at: model.updated_at,
@@ -0,0 +1,35 @@
+use crate::dtos::request::post_media_request_dto::PostMediaRequestDto;
+use crate::dtos::response::post_media_response_dto::PostMediaResponseDto;
+use crate::mappers::post_mapper::{to_srcset, to_webp_srcset};
+use crate::models::post_media::PostMedia;
+use crate::utils::image_util::get_image_key;
+
+pub fn from_dto_to_post_media(dto: &PostMediaRequestDto) -> PostMedia {
+    PostMedia {
+        id: Default::default(),
+        post_id: Default::default(),
+        object_id: Default::default(),
+        content_type: Default::default(),
+        width: Default::default(),
+        widths: Default::default(),
+        position: Default::default(),
+        alt_text: dto.alt_text.clone(),
+        caption: dto.caption.clone(),
+        created_at: Default::default(),
+        updated_at: Default::default(),
+    }
+}
+
+pub fn from_post_media_to_dto(model: &PostMedia) -> PostMediaResponseDto {
+    PostMediaResponseDto {
+        id: model.id.to_string(),
+        url: format!("/assets/images/{}", model.object_id),
+        srcset: to_srcset(model.object_id, model.width, &model.widths, get_image_key),
+        webp_srcset: to_webp_srcset(model.object_id, model.width, &model.widths, &model.content_type),
+        position: model.position,
+        alt_text: model.alt_text.clone(),
+        caption: model.caption.clone(),
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
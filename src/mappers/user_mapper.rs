@@ -0,0 +1,13 @@
+use crate::dtos::response::user_response_dto::UserResponseDto;
+use crate::models::user::User;
+
+pub fn from_user_to_dto(model: &User) -> UserResponseDto {
+    UserResponseDto {
+        id: model.id.to_string(),
+        email: model.email.clone(),
+        username: model.username.clone(),
+        role: model.role.clone(),
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
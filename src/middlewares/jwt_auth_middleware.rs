@@ -1,38 +1,57 @@
 use std::sync::Arc;
 
+use axum::Extension;
 use axum::extract::Request;
-use axum::http::{HeaderMap, StatusCode};
+use axum::http::HeaderMap;
 use axum::http::header::AUTHORIZATION;
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
 
-use crate::config::settings::init_settings;
+use crate::config::settings::Settings;
+use crate::enums::error::Error;
+use crate::services::token_service::TokenService;
 use crate::utils::token_util::{get_bearer_token, parse_jwt_token};
 
+fn unauthorized() -> Response {
+  Error::Unauthorized("Missing or invalid access token.".to_string()).into_response()
+}
+
 pub async fn jwt_auth_middleware(
+  Extension(settings): Extension<Arc<Settings>>,
+  Extension(token_service): Extension<Arc<dyn TokenService>>,
   headers: HeaderMap,
   mut request: Request,
   next: Next,
 ) -> Response {
 
-  let header = headers.get(AUTHORIZATION).unwrap().to_str().unwrap().to_string();
+  let header = match headers.get(AUTHORIZATION).and_then(|header| header.to_str().ok()) {
+    Some(header) => header.to_string(),
+    None => return unauthorized(),
+  };
 
   let result = get_bearer_token(header);
   if result.is_err() {
-    return StatusCode::UNAUTHORIZED.into_response();
+    return unauthorized();
   };
 
   let token = result.unwrap();
 
-  let secret = init_settings().access_secret;
-  let result = parse_jwt_token(token, secret);
+  let secret = settings.access_secret.clone();
+  let result = parse_jwt_token(token.clone(), secret);
   if result.is_err() {
-    return StatusCode::UNAUTHORIZED.into_response();
+    return unauthorized();
   }
 
   let claims = result.unwrap();
 
+  // Tokens are deleted from the cache on logout or when revoked, so a valid signature is not enough.
+  match token_service.get_access_token(claims.sub, claims.jti).await {
+    Ok(Some(stored)) if stored == token => {}
+    Ok(_) => return unauthorized(),
+    Err(error) => return error.into_response(),
+  }
+
   request.extensions_mut().insert(Arc::new(claims));
 
-  return next.run(request).await;
+  next.run(request).await
 }
\ No newline at end of file
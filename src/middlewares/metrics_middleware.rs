@@ -0,0 +1,36 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::Extension;
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::config::metrics::Metrics;
+
+// Route label of requests that matched no route, so unknown paths cannot blow up the label set.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Counts requests and observes their latency by method, route template and status.
+/// The latency runs until the response headers are ready, streamed bodies are not included.
+pub async fn metrics_middleware(
+  Extension(metrics): Extension<Arc<Metrics>>,
+  request: Request,
+  next: Next,
+) -> Response {
+  let method = request.method().to_string();
+  let route = request.extensions()
+    .get::<MatchedPath>()
+    .map(|path| path.as_str().to_string())
+    .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+
+  let started = Instant::now();
+  let response = next.run(request).await;
+  let status = response.status().as_u16().to_string();
+
+  let labels = [method.as_str(), route.as_str(), status.as_str()];
+  metrics.http_requests_total.with_label_values(&labels).inc();
+  metrics.http_request_duration_seconds.with_label_values(&labels).observe(started.elapsed().as_secs_f64());
+
+  response
+}
@@ -1 +1,4 @@
-pub mod jwt_auth_middleware;
\ No newline at end of file
+pub mod jwt_auth_middleware;
+pub mod metrics_middleware;
+pub mod request_id_middleware;
+pub mod trace_middleware;
@@ -0,0 +1,43 @@
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// Longest client supplied request id that is passed through as is.
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+tokio::task_local! {
+  static REQUEST_ID: String;
+}
+
+/// Id of the request being handled on the current task, if any.
+pub fn current_request_id() -> Option<String> {
+  REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Reuses the `X-Request-Id` sent by the client or assigns a new one, makes it
+/// available to the request span and error responses and echoes it back.
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+  let request_id = request.headers()
+    .get(REQUEST_ID_HEADER)
+    .and_then(|value| value.to_str().ok())
+    .filter(|value| !value.is_empty() && value.len() <= MAX_REQUEST_ID_LENGTH)
+    .map(|value| value.to_string())
+    .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+  let header = HeaderValue::from_str(&request_id).ok();
+  if let Some(value) = header.clone() {
+    request.headers_mut().insert(REQUEST_ID_HEADER, value);
+  }
+
+  let mut response = REQUEST_ID.scope(request_id, next.run(request)).await;
+
+  if let Some(value) = header {
+    response.headers_mut().insert(REQUEST_ID_HEADER, value);
+  }
+
+  response
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use axum::extract::MatchedPath;
+use axum::http::{Request, Response};
+use tracing::Span;
+use tracing::field::Empty;
+
+use crate::middlewares::request_id_middleware::REQUEST_ID_HEADER;
+use crate::utils::trace_util::set_remote_parent;
+
+// Route field of requests that matched no route, kept in line with the metrics label.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Root span of a request, every log line written while handling it carries these fields.
+/// The span closes once the response body is sent, its close event is the access log line.
+/// Expects `request_id_middleware` to run first so the id is already on the request.
+/// The `otel.*` fields name the exported server span and flag server errors.
+pub fn make_request_span<B>(request: &Request<B>) -> Span {
+  let request_id = request.headers()
+    .get(REQUEST_ID_HEADER)
+    .and_then(|value| value.to_str().ok())
+    .unwrap_or_default();
+  let route = request.extensions()
+    .get::<MatchedPath>()
+    .map(|path| path.as_str())
+    .unwrap_or(UNMATCHED_ROUTE);
+
+  let span = tracing::info_span!(
+    "request",
+    request_id = %request_id,
+    method = %request.method(),
+    route = %route,
+    path = %request.uri().path(),
+    status = Empty,
+    otel.name = %format!("{} {}", request.method(), route),
+    otel.kind = "server",
+    otel.status_code = Empty,
+  );
+  set_remote_parent(&span, request.headers());
+
+  span
+}
+
+pub fn record_response_status<B>(response: &Response<B>, _latency: Duration, span: &Span) {
+  span.record("status", response.status().as_u16());
+  if response.status().is_server_error() {
+    span.record("otel.status_code", "error");
+  }
+}
@@ -0,0 +1,5 @@
+/// Changes to apply to a comment, `None` leaves the field untouched.
+#[derive(Debug, Default)]
+pub struct CommentPatch {
+    pub body: Option<String>,
+}
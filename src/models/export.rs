@@ -0,0 +1,13 @@
+use sqlx::types::Uuid;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Export {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub status: String,
+    pub object_id: Option<Uuid>,
+    pub download_token: Uuid,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
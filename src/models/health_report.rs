@@ -0,0 +1,16 @@
+use crate::enums::health_status::HealthStatus;
+
+#[derive(Debug, Clone)]
+pub struct DependencyHealth {
+    pub name: String,
+    pub status: HealthStatus,
+    pub critical: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub dependencies: Vec<DependencyHealth>,
+}
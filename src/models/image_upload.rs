@@ -0,0 +1,8 @@
+use sqlx::types::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct ImageUpload {
+    pub key: Uuid,
+    pub url: String,
+    pub expires_in: u32,
+}
@@ -0,0 +1,8 @@
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+    /// False when the applied migration differs from the one embedded in the binary.
+    pub checksum_matches: bool,
+}
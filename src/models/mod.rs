@@ -2,3 +2,16 @@ pub mod user;
 pub mod post;
 pub mod token_claims;
 pub mod comment;
+pub mod export;
+pub mod store_object;
+pub mod image_upload;
+pub mod post_media;
+pub mod store_entry;
+pub mod storage_gc_report;
+pub mod pending_deletion;
+pub mod field_error;
+pub mod post_patch;
+pub mod comment_patch;
+pub mod health_report;
+pub mod pool_stats;
+pub mod migration_status;
@@ -0,0 +1,10 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: u32,
+    pub max_connections: u32,
+    /// None when no connection could be acquired in time.
+    pub acquire_wait: Option<Duration>,
+}
@@ -1,12 +1,19 @@
 use sqlx::types::Uuid;
 
+use crate::models::post_media::PostMedia;
+
 #[derive(Debug, sqlx::FromRow)]
 pub struct Post {
     pub id: Uuid,
     pub title: String,
     pub body: String,
     pub image_id: Uuid,
+    pub image_width: i32,
+    pub image_widths: Vec<i32>,
+    pub image_content_type: String,
     pub user_id: Uuid,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
-}
\ No newline at end of file
+    #[sqlx(skip)]
+    pub media: Vec<PostMedia>,
+}
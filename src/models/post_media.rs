@@ -0,0 +1,16 @@
+use sqlx::types::Uuid;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PostMedia {
+    pub id: Uuid,
+    pub post_id: Uuid,
+    pub object_id: Uuid,
+    pub content_type: String,
+    pub width: i32,
+    pub widths: Vec<i32>,
+    pub position: i32,
+    pub alt_text: String,
+    pub caption: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
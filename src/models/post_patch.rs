@@ -0,0 +1,7 @@
+/// Changes to apply to a post, `None` leaves the field untouched.
+#[derive(Debug, Default)]
+pub struct PostPatch {
+    pub title: Option<String>,
+    pub body: Option<String>,
+    pub clear_image: bool,
+}
@@ -0,0 +1,7 @@
+#[derive(Debug, Clone, Default)]
+pub struct StorageGcReport {
+    pub dry_run: bool,
+    pub scanned: usize,
+    pub deleted_keys: Vec<String>,
+    pub reclaimed_bytes: u64,
+}
@@ -0,0 +1,8 @@
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone)]
+pub struct StoreEntry {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: DateTime<Utc>,
+}
@@ -0,0 +1,8 @@
+#[derive(Debug, Clone)]
+pub struct StoreObject {
+    pub key: String,
+    pub content_type: String,
+    pub content_length: u64,
+    pub e_tag: Option<String>,
+    pub last_modified: Option<String>,
+}
@@ -9,4 +9,6 @@ pub struct TokenClaims {
     pub iat: i64,         // Issued At (Unix timestamp)
     pub exp: i64,         // Expiration (Unix timestamp)
     pub is_refresh: bool, // Custom claim indicating whether it's a refresh token
+    #[serde(default)]
+    pub role: String,     // Custom claim with the role of the user
 }
\ No newline at end of file
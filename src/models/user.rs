@@ -6,6 +6,7 @@ pub struct User {
     pub email: String,
     pub username: String,
     pub password: String,
+    pub role: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -15,4 +16,5 @@ pub struct CreateUser {
     pub email: String,
     pub username: String,
     pub password: String,
+    pub role: String,
 }
\ No newline at end of file
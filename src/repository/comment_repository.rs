@@ -12,8 +12,10 @@ use crate::models::comment::Comment;
 pub trait CommentRepository: Send + Sync {
   async fn get_all(&self) -> Result<Vec<Comment>, Error>;
   async fn get_all_for_post(&self, post_id: Uuid) -> Result<Vec<Comment>, Error>;
+  async fn get_all_for_user(&self, user_id: Uuid) -> Result<Vec<Comment>, Error>;
   async fn get_one(&self, id: Uuid) -> Result<Comment, Error>;
   async fn create(&self, comment: Comment) -> Result<Comment, Error>;
+  /// Fails with a conflict when the comment changed since `comment.updated_at`.
   async fn update(&self, comment: Comment) -> Result<Comment, Error>;
 }
 
@@ -30,6 +32,7 @@ impl CommentRepositoryImpl {
 
 #[async_trait]
 impl CommentRepository for CommentRepositoryImpl {
+  #[tracing::instrument(name = "CommentRepository::get_all", level = "debug", skip_all)]
   async fn get_all(&self) -> Result<Vec<Comment>, Error> {
     let conn = self.pool.deref();
     sqlx::query_as!(
@@ -40,9 +43,12 @@ impl CommentRepository for CommentRepositoryImpl {
         )
       .fetch_all(conn)
       .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map_err(Error::from)
   }
 
+  #[tracing::instrument(
+    name = "CommentRepository::get_all_for_post", level = "debug", skip_all, fields(post_id = %post_id),
+  )]
   async fn get_all_for_post(&self, post_id: Uuid) -> Result<Vec<Comment>, Error> {
     let conn = self.pool.deref();
     sqlx::query_as!(
@@ -55,9 +61,28 @@ impl CommentRepository for CommentRepositoryImpl {
         )
       .fetch_all(conn)
       .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map_err(Error::from)
   }
 
+  #[tracing::instrument(
+    name = "CommentRepository::get_all_for_user", level = "debug", skip_all, fields(user_id = %user_id),
+  )]
+  async fn get_all_for_user(&self, user_id: Uuid) -> Result<Vec<Comment>, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_as!(
+            Comment,
+            r#"
+            SELECT * FROM comments
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+      .fetch_all(conn)
+      .await
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "CommentRepository::get_one", level = "debug", skip_all, fields(id = %id))]
   async fn get_one(&self, id: Uuid) -> Result<Comment, Error> {
     let conn = self.pool.deref();
     sqlx::query_as!(
@@ -70,9 +95,10 @@ impl CommentRepository for CommentRepositoryImpl {
         )
       .fetch_one(conn)
       .await
-      .map_err(|_| Error::NotFound("Not found.".to_string()))
+      .map_err(Error::from)
   }
 
+  #[tracing::instrument(name = "CommentRepository::create", level = "debug", skip_all)]
   async fn create(&self, comment: Comment) -> Result<Comment, Error> {
     let conn = self.pool.deref();
     sqlx::query_as!(
@@ -88,9 +114,10 @@ impl CommentRepository for CommentRepositoryImpl {
         )
       .fetch_one(conn)
       .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map_err(Error::from)
   }
 
+  #[tracing::instrument(name = "CommentRepository::update", level = "debug", skip_all, fields(id = %comment.id))]
   async fn update(&self, comment: Comment) -> Result<Comment, Error> {
     let conn = self.pool.deref();
     sqlx::query_as!(
@@ -98,16 +125,18 @@ impl CommentRepository for CommentRepositoryImpl {
             r#"
             UPDATE comments
             SET body = $1, user_id = $2, post_id = $3
-            WHERE id = $4
+            WHERE id = $4 AND updated_at = $5
             RETURNING *
             "#,
             comment.body,
             comment.user_id,
             comment.post_id,
-            comment.id
+            comment.id,
+            comment.updated_at
         )
-      .fetch_one(conn)
+      .fetch_optional(conn)
       .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map_err(Error::from)?
+      .ok_or_else(|| Error::Conflict("The comment was modified concurrently.".to_string()))
   }
 }
\ No newline at end of file
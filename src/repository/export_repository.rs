@@ -0,0 +1,120 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+
+use crate::enums::error::Error;
+use crate::models::export::Export;
+
+#[async_trait]
+pub trait ExportRepository: Send + Sync {
+  async fn get_one(&self, id: Uuid) -> Result<Export, Error>;
+  async fn get_pending(&self, user_id: Uuid) -> Result<Option<Export>, Error>;
+  /// Returns `None` when the user already has a pending export.
+  async fn create(&self, user_id: Uuid) -> Result<Option<Export>, Error>;
+  async fn update(&self, export: Export) -> Result<Export, Error>;
+  /// Marks every pending export failed, returning how many were.
+  async fn fail_pending(&self) -> Result<u64, Error>;
+}
+
+pub struct ExportRepositoryImpl {
+  pool: Arc<PgPool>,
+}
+
+impl ExportRepositoryImpl {
+  pub fn new(pool: Arc<PgPool>) -> Self {
+    ExportRepositoryImpl { pool }
+  }
+}
+
+#[async_trait]
+impl ExportRepository for ExportRepositoryImpl {
+  #[tracing::instrument(name = "ExportRepository::get_one", level = "debug", skip_all, fields(id = %id))]
+  async fn get_one(&self, id: Uuid) -> Result<Export, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_as!(
+            Export,
+            r#"
+            SELECT * FROM exports
+            WHERE id = $1
+            "#,
+            id
+        )
+      .fetch_one(conn)
+      .await
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "ExportRepository::get_pending", level = "debug", skip_all, fields(user_id = %user_id))]
+  async fn get_pending(&self, user_id: Uuid) -> Result<Option<Export>, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_as!(
+            Export,
+            r#"
+            SELECT * FROM exports
+            WHERE user_id = $1 AND status = 'pending'
+            "#,
+            user_id
+        )
+      .fetch_optional(conn)
+      .await
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "ExportRepository::create", level = "debug", skip_all, fields(user_id = %user_id))]
+  async fn create(&self, user_id: Uuid) -> Result<Option<Export>, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_as!(
+            Export,
+            r#"
+            INSERT INTO exports (user_id)
+            VALUES ($1)
+            ON CONFLICT (user_id) WHERE status = 'pending' DO NOTHING
+            RETURNING *
+            "#,
+            user_id
+        )
+      .fetch_optional(conn)
+      .await
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "ExportRepository::update", level = "debug", skip_all, fields(id = %export.id))]
+  async fn update(&self, export: Export) -> Result<Export, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_as!(
+            Export,
+            r#"
+            UPDATE exports
+            SET status = $1, object_id = $2, expires_at = $3
+            WHERE id = $4
+            RETURNING *
+            "#,
+            export.status,
+            export.object_id,
+            export.expires_at,
+            export.id
+        )
+      .fetch_one(conn)
+      .await
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "ExportRepository::fail_pending", level = "debug", skip_all)]
+  async fn fail_pending(&self) -> Result<u64, Error> {
+    let conn = self.pool.deref();
+    sqlx::query!(
+            r#"
+            UPDATE exports
+            SET status = 'failed'
+            WHERE status = 'pending'
+            "#
+        )
+      .execute(conn)
+      .await
+      .map(|result| result.rows_affected())
+      .map_err(Error::from)
+  }
+}
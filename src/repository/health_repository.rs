@@ -0,0 +1,64 @@
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use crate::enums::error::Error;
+use crate::models::pool_stats::PoolStats;
+
+// Bounds the acquire probe so a saturated pool cannot stall a scrape.
+const ACQUIRE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[async_trait]
+pub trait HealthRepository: Send + Sync {
+  /// Runs a trivial query on a pooled connection.
+  async fn ping(&self) -> Result<(), Error>;
+  /// Current pool usage and how long acquiring a connection takes right now.
+  async fn pool_stats(&self) -> PoolStats;
+}
+
+pub struct HealthRepositoryImpl {
+  pool: Arc<PgPool>,
+}
+
+impl HealthRepositoryImpl {
+  pub fn new(pool: Arc<PgPool>) -> Self {
+    HealthRepositoryImpl { pool }
+  }
+}
+
+#[async_trait]
+impl HealthRepository for HealthRepositoryImpl {
+  #[tracing::instrument(name = "HealthRepository::ping", level = "debug", skip_all)]
+  async fn ping(&self) -> Result<(), Error> {
+    let conn = self.pool.deref();
+    sqlx::query("SELECT 1")
+      .execute(conn)
+      .await
+      .map(|_| ())
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "HealthRepository::pool_stats", level = "debug", skip_all)]
+  async fn pool_stats(&self) -> PoolStats {
+    // Sampled before the probe so its own connection is not counted as in use.
+    let size = self.pool.size();
+    let idle = self.pool.num_idle() as u32;
+
+    let started = Instant::now();
+    let acquire_wait = tokio::time::timeout(ACQUIRE_TIMEOUT, self.pool.acquire())
+      .await
+      .ok()
+      .and_then(Result::ok)
+      .map(|_| started.elapsed());
+
+    PoolStats {
+      size,
+      idle,
+      max_connections: self.pool.options().get_max_connections(),
+      acquire_wait,
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,92 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+
+use crate::enums::error::Error;
+
+#[async_trait]
+pub trait ImageUploadRepository: Send + Sync {
+  /// Records an upload issued for a post and returns its key. Expired uploads of the post are dropped.
+  async fn create(&self, post_id: Uuid, expires_at: DateTime<Utc>) -> Result<Uuid, Error>;
+  /// Whether the key was issued for the post and is neither expired nor used.
+  async fn is_pending(&self, post_id: Uuid, id: Uuid) -> Result<bool, Error>;
+  /// Marks a pending upload as used, false when it is not pending anymore.
+  async fn mark_used(&self, post_id: Uuid, id: Uuid) -> Result<bool, Error>;
+}
+
+pub struct ImageUploadRepositoryImpl {
+  pool: Arc<PgPool>,
+}
+
+impl ImageUploadRepositoryImpl {
+  pub fn new(pool: Arc<PgPool>) -> Self {
+    ImageUploadRepositoryImpl { pool }
+  }
+}
+
+#[async_trait]
+impl ImageUploadRepository for ImageUploadRepositoryImpl {
+  #[tracing::instrument(name = "ImageUploadRepository::create", level = "debug", skip_all, fields(post_id = %post_id))]
+  async fn create(&self, post_id: Uuid, expires_at: DateTime<Utc>) -> Result<Uuid, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_scalar!(
+            r#"
+            WITH expired AS (
+                DELETE FROM image_uploads
+                WHERE post_id = $1 AND expires_at <= now()
+            )
+            INSERT INTO image_uploads (post_id, expires_at)
+            VALUES ($1, $2)
+            RETURNING id
+            "#,
+            post_id,
+            expires_at
+        )
+      .fetch_one(conn)
+      .await
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(
+    name = "ImageUploadRepository::is_pending", level = "debug", skip_all, fields(post_id = %post_id, id = %id),
+  )]
+  async fn is_pending(&self, post_id: Uuid, id: Uuid) -> Result<bool, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM image_uploads
+                WHERE id = $1 AND post_id = $2 AND used_at IS NULL AND expires_at > now()
+            ) AS "exists!"
+            "#,
+            id,
+            post_id
+        )
+      .fetch_one(conn)
+      .await
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(
+    name = "ImageUploadRepository::mark_used", level = "debug", skip_all, fields(post_id = %post_id, id = %id),
+  )]
+  async fn mark_used(&self, post_id: Uuid, id: Uuid) -> Result<bool, Error> {
+    let conn = self.pool.deref();
+    sqlx::query!(
+            r#"
+            UPDATE image_uploads
+            SET used_at = now()
+            WHERE id = $1 AND post_id = $2 AND used_at IS NULL AND expires_at > now()
+            "#,
+            id,
+            post_id
+        ).execute(conn)
+      .await
+      .map(|result| result.rows_affected() == 1)
+      .map_err(Error::from)
+  }
+}
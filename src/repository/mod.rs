@@ -1,4 +1,10 @@
 pub mod post_repository;
 pub mod comment_repository;
+pub mod export_repository;
+pub mod health_repository;
+pub mod image_upload_repository;
+pub mod pending_deletion_repository;
+pub mod post_media_repository;
+pub mod storage_repository;
 pub mod token_repository;
 pub mod user_repository;
@@ -0,0 +1,128 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use sqlx::types::Uuid;
+
+use crate::enums::error::Error;
+use crate::models::pending_deletion::PendingDeletion;
+use crate::utils::store_key_util::EXPORT_KEY_PREFIX;
+
+/// Queues store keys for deletion as part of a larger transaction, so objects
+/// are only removed once the rows pointing at them are gone for good.
+pub async fn enqueue_deletions(tx: &mut Transaction<'_, Postgres>, keys: &[String]) -> Result<(), Error> {
+  if keys.is_empty() {
+    return Ok(());
+  }
+
+  sqlx::query!(
+            r#"
+            INSERT INTO pending_deletions (key)
+            SELECT * FROM UNNEST($1::varchar[])
+            "#,
+            keys
+        ).execute(&mut **tx)
+    .await
+    .map(|_| ())
+    .map_err(Error::from)
+}
+
+#[async_trait]
+pub trait PendingDeletionRepository: Send + Sync {
+  async fn get_due(&self, limit: i64) -> Result<Vec<PendingDeletion>, Error>;
+  /// Detaches the archives of expired exports and queues them, returning how many were queued.
+  async fn enqueue_expired_exports(&self) -> Result<u64, Error>;
+  async fn delete(&self, id: Uuid) -> Result<(), Error>;
+  async fn reschedule(&self, id: Uuid, error: String, next_attempt_at: DateTime<Utc>) -> Result<(), Error>;
+}
+
+pub struct PendingDeletionRepositoryImpl {
+  pool: Arc<PgPool>,
+}
+
+impl PendingDeletionRepositoryImpl {
+  pub fn new(pool: Arc<PgPool>) -> Self {
+    PendingDeletionRepositoryImpl { pool }
+  }
+}
+
+#[async_trait]
+impl PendingDeletionRepository for PendingDeletionRepositoryImpl {
+  #[tracing::instrument(name = "PendingDeletionRepository::get_due", level = "debug", skip_all)]
+  async fn get_due(&self, limit: i64) -> Result<Vec<PendingDeletion>, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_as!(
+            PendingDeletion,
+            r#"
+            SELECT * FROM pending_deletions
+            WHERE next_attempt_at <= now()
+            ORDER BY next_attempt_at
+            LIMIT $1
+            "#,
+            limit
+        )
+      .fetch_all(conn)
+      .await
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "PendingDeletionRepository::enqueue_expired_exports", level = "debug", skip_all)]
+  async fn enqueue_expired_exports(&self) -> Result<u64, Error> {
+    let conn = self.pool.deref();
+    // One statement, so an archive is never detached without being queued.
+    sqlx::query!(
+            r#"
+            WITH expired AS (
+                SELECT id, object_id FROM exports
+                WHERE object_id IS NOT NULL AND expires_at <= now()
+                FOR UPDATE SKIP LOCKED
+            ), detached AS (
+                UPDATE exports SET object_id = NULL
+                FROM expired
+                WHERE exports.id = expired.id
+            )
+            INSERT INTO pending_deletions (key)
+            SELECT $1 || object_id::varchar FROM expired
+            "#,
+            EXPORT_KEY_PREFIX
+        ).execute(conn)
+      .await
+      .map(|result| result.rows_affected())
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "PendingDeletionRepository::delete", level = "debug", skip_all, fields(id = %id))]
+  async fn delete(&self, id: Uuid) -> Result<(), Error> {
+    let conn = self.pool.deref();
+    sqlx::query!(
+            r#"
+            DELETE FROM pending_deletions
+            WHERE id = $1
+            "#,
+            id
+        ).execute(conn)
+      .await
+      .map(|_| ())
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "PendingDeletionRepository::reschedule", level = "debug", skip_all, fields(id = %id))]
+  async fn reschedule(&self, id: Uuid, error: String, next_attempt_at: DateTime<Utc>) -> Result<(), Error> {
+    let conn = self.pool.deref();
+    sqlx::query!(
+            r#"
+            UPDATE pending_deletions
+            SET attempts = attempts + 1, last_error = $1, next_attempt_at = $2
+            WHERE id = $3
+            "#,
+            error,
+            next_attempt_at,
+            id
+        ).execute(conn)
+      .await
+      .map(|_| ())
+      .map_err(Error::from)
+  }
+}
@@ -0,0 +1,184 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+
+use crate::enums::error::Error;
+use crate::models::post_media::PostMedia;
+use crate::repository::pending_deletion_repository::enqueue_deletions;
+
+#[async_trait]
+pub trait PostMediaRepository: Send + Sync {
+  async fn get_all_for_posts(&self, post_ids: &[Uuid]) -> Result<Vec<PostMedia>, Error>;
+  async fn get_one(&self, post_id: Uuid, id: Uuid) -> Result<PostMedia, Error>;
+  async fn create(&self, media: PostMedia) -> Result<PostMedia, Error>;
+  async fn update(&self, media: PostMedia) -> Result<PostMedia, Error>;
+  async fn update_positions(&self, post_id: Uuid, ids: &[Uuid]) -> Result<(), Error>;
+  /// Deletes the media and, in the same transaction, queues `deleted_keys` for removal from the store.
+  async fn delete(&self, id: Uuid, deleted_keys: Vec<String>) -> Result<(), Error>;
+}
+
+pub struct PostMediaRepositoryImpl {
+  pool: Arc<PgPool>,
+}
+
+impl PostMediaRepositoryImpl {
+  pub fn new(pool: Arc<PgPool>) -> Self {
+    PostMediaRepositoryImpl { pool }
+  }
+}
+
+#[async_trait]
+impl PostMediaRepository for PostMediaRepositoryImpl {
+  #[tracing::instrument(name = "PostMediaRepository::get_all_for_posts", level = "debug", skip_all)]
+  async fn get_all_for_posts(&self, post_ids: &[Uuid]) -> Result<Vec<PostMedia>, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_as!(
+            PostMedia,
+            r#"
+            SELECT * FROM post_media
+            WHERE post_id = ANY($1)
+            ORDER BY post_id, position, created_at
+            "#,
+            post_ids
+        )
+      .fetch_all(conn)
+      .await
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(
+    name = "PostMediaRepository::get_one", level = "debug", skip_all, fields(post_id = %post_id, id = %id),
+  )]
+  async fn get_one(&self, post_id: Uuid, id: Uuid) -> Result<PostMedia, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_as!(
+            PostMedia,
+            r#"
+            SELECT * FROM post_media
+            WHERE post_id = $1 AND id = $2
+            "#,
+            post_id,
+            id
+        )
+      .fetch_one(conn)
+      .await
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "PostMediaRepository::create", level = "debug", skip_all)]
+  async fn create(&self, media: PostMedia) -> Result<PostMedia, Error> {
+    let mut tx = self.pool
+      .begin()
+      .await
+      .map_err(Error::from)?;
+
+    // Locking the post serialises concurrent adds, so each one appends after the last position.
+    sqlx::query!(
+            r#"
+            SELECT id FROM posts
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+            media.post_id
+        ).fetch_optional(&mut *tx)
+      .await
+      .map_err(Error::from)?
+      .ok_or_else(|| Error::NotFound("Not found.".to_string()))?;
+
+    let media = sqlx::query_as!(
+            PostMedia,
+            r#"
+            INSERT INTO post_media (post_id, object_id, content_type, width, widths, position, alt_text, caption)
+            VALUES ($1, $2, $3, $4, $5, (SELECT COALESCE(MAX(position) + 1, 0) FROM post_media WHERE post_id = $1), $6, $7)
+            RETURNING *
+            "#,
+            media.post_id,
+            media.object_id,
+            media.content_type,
+            media.width,
+            &media.widths,
+            media.alt_text,
+            media.caption
+        )
+      .fetch_one(&mut *tx)
+      .await
+      .map_err(Error::from)?;
+
+    tx.commit()
+      .await
+      .map_err(Error::from)?;
+
+    Ok(media)
+  }
+
+  #[tracing::instrument(name = "PostMediaRepository::update", level = "debug", skip_all, fields(id = %media.id))]
+  async fn update(&self, media: PostMedia) -> Result<PostMedia, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_as!(
+            PostMedia,
+            r#"
+            UPDATE post_media
+            SET alt_text = $1, caption = $2
+            WHERE id = $3
+            RETURNING *
+            "#,
+            media.alt_text,
+            media.caption,
+            media.id
+        )
+      .fetch_one(conn)
+      .await
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(
+    name = "PostMediaRepository::update_positions", level = "debug", skip_all, fields(post_id = %post_id),
+  )]
+  async fn update_positions(&self, post_id: Uuid, ids: &[Uuid]) -> Result<(), Error> {
+    let conn = self.pool.deref();
+    sqlx::query!(
+            r#"
+            UPDATE post_media
+            SET position = ordered.position::integer - 1
+            FROM UNNEST($2::uuid[]) WITH ORDINALITY AS ordered (id, position)
+            WHERE post_media.post_id = $1 AND post_media.id = ordered.id
+            "#,
+            post_id,
+            ids
+        ).execute(conn)
+      .await
+      .map(|_| ())
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "PostMediaRepository::delete", level = "debug", skip_all, fields(id = %id))]
+  async fn delete(&self, id: Uuid, deleted_keys: Vec<String>) -> Result<(), Error> {
+    let mut tx = self.pool
+      .begin()
+      .await
+      .map_err(Error::from)?;
+
+    let result = sqlx::query!(
+            r#"
+            DELETE FROM post_media
+            WHERE id = $1
+            "#,
+            id
+        ).execute(&mut *tx)
+      .await
+      .map_err(Error::from)?;
+
+    if result.rows_affected() == 0 {
+      return Err(Error::NotFound("Not found.".to_string()));
+    }
+
+    enqueue_deletions(&mut tx, &deleted_keys).await?;
+
+    tx.commit()
+      .await
+      .map_err(Error::from)
+  }
+}
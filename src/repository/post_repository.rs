@@ -2,11 +2,13 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use sqlx::types::Uuid;
 
 use crate::enums::error::Error;
 use crate::models::post::Post;
+use crate::repository::pending_deletion_repository::enqueue_deletions;
 
 #[derive(Debug, sqlx::FromRow)]
 pub struct NewPost {
@@ -16,13 +18,50 @@ pub struct NewPost {
   pub user_id: Uuid,
 }
 
+#[derive(Debug, sqlx::FromRow)]
+struct PostRow {
+  id: Uuid,
+  title: String,
+  body: String,
+  image_id: Uuid,
+  image_width: i32,
+  image_widths: Vec<i32>,
+  image_content_type: String,
+  user_id: Uuid,
+  created_at: chrono::DateTime<chrono::Utc>,
+  updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<PostRow> for Post {
+  fn from(row: PostRow) -> Self {
+    Post {
+      id: row.id,
+      title: row.title,
+      body: row.body,
+      image_id: row.image_id,
+      image_width: row.image_width,
+      image_widths: row.image_widths,
+      image_content_type: row.image_content_type,
+      user_id: row.user_id,
+      created_at: row.created_at,
+      updated_at: row.updated_at,
+      media: Vec::new(),
+    }
+  }
+}
+
 #[async_trait]
 pub trait PostRepository: Send + Sync {
   async fn get_all(&self) -> Result<Vec<Post>, Error>;
+  async fn get_all_for_user(&self, user_id: Uuid) -> Result<Vec<Post>, Error>;
   async fn get_one(&self, id: Uuid) -> Result<Post, Error>;
   async fn create(&self, post: Post) -> Result<Post, Error>;
-  async fn update(&self, post: Post) -> Result<Post, Error>;
-  async fn delete(&self, id: Uuid) -> Result<(), Error>;
+  /// Updates the post and, in the same transaction, queues `deleted_keys` for removal from the store.
+  /// Fails with a conflict when the post changed since `post.updated_at`.
+  async fn update(&self, post: Post, deleted_keys: Vec<String>) -> Result<Post, Error>;
+  /// Deletes the post and, in the same transaction, queues `deleted_keys` for removal from the store.
+  /// Fails with a conflict when the post changed since `updated_at`.
+  async fn delete(&self, id: Uuid, updated_at: DateTime<Utc>, deleted_keys: Vec<String>) -> Result<(), Error>;
 }
 
 pub struct PostRepositoryImpl {
@@ -37,23 +76,45 @@ impl PostRepositoryImpl {
 
 #[async_trait]
 impl PostRepository for PostRepositoryImpl {
+  #[tracing::instrument(name = "PostRepository::get_all", level = "debug", skip_all)]
   async fn get_all(&self) -> Result<Vec<Post>, Error> {
     let conn = self.pool.deref();
     sqlx::query_as!(
-            Post,
+            PostRow,
             r#"
             SELECT * FROM posts
             "#
         )
       .fetch_all(conn)
       .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map(|rows| rows.into_iter().map(Post::from).collect())
+      .map_err(Error::from)
   }
 
+  #[tracing::instrument(
+    name = "PostRepository::get_all_for_user", level = "debug", skip_all, fields(user_id = %user_id),
+  )]
+  async fn get_all_for_user(&self, user_id: Uuid) -> Result<Vec<Post>, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_as!(
+            PostRow,
+            r#"
+            SELECT * FROM posts
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+      .fetch_all(conn)
+      .await
+      .map(|rows| rows.into_iter().map(Post::from).collect())
+      .map_err(Error::from)
+  }
+
+  #[tracing::instrument(name = "PostRepository::get_one", level = "debug", skip_all, fields(id = %id))]
   async fn get_one(&self, id: Uuid) -> Result<Post, Error> {
     let conn = self.pool.deref();
     sqlx::query_as!(
-            Post,
+            PostRow,
             r#"
             SELECT * FROM posts
             WHERE id = $1
@@ -62,13 +123,15 @@ impl PostRepository for PostRepositoryImpl {
         )
       .fetch_one(conn)
       .await
-      .map_err(|_| Error::NotFound("Not found.".to_string()))
+      .map(Post::from)
+      .map_err(Error::from)
   }
 
+  #[tracing::instrument(name = "PostRepository::create", level = "debug", skip_all)]
   async fn create(&self, post: Post) -> Result<Post, Error> {
     let conn = self.pool.deref();
     sqlx::query_as!(
-            Post,
+            PostRow,
             r#"
             INSERT INTO posts (title, body, image_id, user_id)
             VALUES ($1, $2, $3, $4)
@@ -81,41 +144,77 @@ impl PostRepository for PostRepositoryImpl {
         )
       .fetch_one(conn)
       .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map(Post::from)
+      .map_err(Error::from)
   }
 
-  async fn update(&self, post: Post) -> Result<Post, Error> {
-    let conn = self.pool.deref();
-    sqlx::query_as!(
-            Post,
+  #[tracing::instrument(name = "PostRepository::update", level = "debug", skip_all, fields(id = %post.id))]
+  async fn update(&self, post: Post, deleted_keys: Vec<String>) -> Result<Post, Error> {
+    let mut tx = self.pool
+      .begin()
+      .await
+      .map_err(Error::from)?;
+
+    let post = sqlx::query_as!(
+            PostRow,
             r#"
             UPDATE posts
-            SET title = $1, body = $2, image_id = $3, user_id = $4
-            WHERE id = $5
+            SET title = $1, body = $2, image_id = $3, image_width = $4, image_widths = $5, image_content_type = $6,
+                user_id = $7
+            WHERE id = $8 AND updated_at = $9
             RETURNING *
             "#,
             post.title,
             post.body,
             post.image_id,
+            post.image_width,
+            &post.image_widths,
+            post.image_content_type,
             post.user_id,
-            post.id
+            post.id,
+            post.updated_at
         )
-      .fetch_one(conn)
+      .fetch_optional(&mut *tx)
       .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map_err(Error::from)?
+      .map(Post::from)
+      .ok_or_else(|| Error::Conflict("The post was modified concurrently.".to_string()))?;
+
+    enqueue_deletions(&mut tx, &deleted_keys).await?;
+
+    tx.commit()
+      .await
+      .map_err(Error::from)?;
+
+    Ok(post)
   }
 
-  async fn delete(&self, id: Uuid) -> Result<(), Error> {
-    let conn = self.pool.deref();
-    sqlx::query!(
+  #[tracing::instrument(name = "PostRepository::delete", level = "debug", skip_all, fields(id = %id))]
+  async fn delete(&self, id: Uuid, updated_at: DateTime<Utc>, deleted_keys: Vec<String>) -> Result<(), Error> {
+    let mut tx = self.pool
+      .begin()
+      .await
+      .map_err(Error::from)?;
+
+    let result = sqlx::query!(
             r#"
             DELETE FROM posts
-            WHERE id = $1
+            WHERE id = $1 AND updated_at = $2
             "#,
-            id
-        ).execute(conn)
+            id,
+            updated_at
+        ).execute(&mut *tx)
+      .await
+      .map_err(Error::from)?;
+
+    if result.rows_affected() == 0 {
+      return Err(Error::Conflict("The post was modified concurrently.".to_string()));
+    }
+
+    enqueue_deletions(&mut tx, &deleted_keys).await?;
+
+    tx.commit()
       .await
-      .map(|_| ())
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map_err(Error::from)
   }
 }
@@ -0,0 +1,46 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+
+use crate::enums::error::Error;
+
+#[async_trait]
+pub trait StorageRepository: Send + Sync {
+  /// Ids of every stored object still referenced by a post, post media, export or pending upload.
+  async fn get_referenced_object_ids(&self) -> Result<Vec<Uuid>, Error>;
+}
+
+pub struct StorageRepositoryImpl {
+  pool: Arc<PgPool>,
+}
+
+impl StorageRepositoryImpl {
+  pub fn new(pool: Arc<PgPool>) -> Self {
+    StorageRepositoryImpl { pool }
+  }
+}
+
+#[async_trait]
+impl StorageRepository for StorageRepositoryImpl {
+  #[tracing::instrument(name = "StorageRepository::get_referenced_object_ids", level = "debug", skip_all)]
+  async fn get_referenced_object_ids(&self) -> Result<Vec<Uuid>, Error> {
+    let conn = self.pool.deref();
+    sqlx::query_scalar!(
+            r#"
+            SELECT image_id AS "id!" FROM posts
+            UNION
+            SELECT object_id FROM post_media
+            UNION
+            SELECT object_id AS "id!" FROM exports WHERE object_id IS NOT NULL
+            UNION
+            SELECT id FROM image_uploads WHERE used_at IS NULL AND expires_at > now()
+            "#
+        )
+      .fetch_all(conn)
+      .await
+      .map_err(Error::from)
+  }
+}
@@ -1,56 +1,115 @@
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
-use chrono::Duration;
-use redis::aio::Connection;
-use redis::AsyncCommands;
-use tokio::sync::Mutex;
+use redis::aio::ConnectionManager;
+use redis::{AsyncCommands, RedisResult};
 
+use crate::config::metrics::Metrics;
 use crate::enums::error::Error;
 
+// Keys looked at per SCAN call, each call is bounded by the command timeout on its own.
+const SCAN_COUNT: usize = 500;
+
 #[async_trait]
 pub trait TokenRepository: Send + Sync {
-  async fn get_token(&self, key: String) -> Result<String, Error>;
-  async fn save_token(&self, key: String, token: String, exp: Duration) -> Result<bool, Error>;
+  async fn get_token(&self, key: String) -> Result<Option<String>, Error>;
+  /// Gets and deletes the token in one command, so of two concurrent callers only one gets it.
+  async fn take_token(&self, key: String) -> Result<Option<String>, Error>;
+  async fn save_token(&self, key: String, token: String, exp: chrono::Duration) -> Result<bool, Error>;
   async fn delete_token(&self, key: String) -> Result<bool, Error>;
+  async fn delete_tokens_with_prefix(&self, prefix: String) -> Result<usize, Error>;
+  async fn ping(&self) -> Result<(), Error>;
 }
 
 pub struct TokenRepositoryImpl {
-  redis: Arc<Mutex<Connection>>,
+  redis: ConnectionManager,
+  timeout: Duration,
+  metrics: Arc<Metrics>,
 }
 
 impl TokenRepositoryImpl {
-  pub fn new(redis: Arc<Mutex<Connection>>) -> Self {
-    TokenRepositoryImpl { redis }
+  pub fn new(redis: ConnectionManager, timeout: Duration, metrics: Arc<Metrics>) -> Self {
+    TokenRepositoryImpl { redis, timeout, metrics }
+  }
+
+  /// Runs a command on a clone of the shared connection, so commands are pipelined instead
+  /// of queued. Observes its latency and gives up after the configured timeout; a dropped
+  /// connection fails the command and is re-established in the background.
+  async fn run<T, F>(&self, command: &str, query: impl FnOnce(ConnectionManager) -> F) -> Result<T, Error>
+  where
+    F: Future<Output = RedisResult<T>>,
+  {
+    let _timer = self.metrics.redis_command_duration_seconds.with_label_values(&[command]).start_timer();
+
+    match tokio::time::timeout(self.timeout, query(self.redis.clone())).await {
+      Ok(result) => result.map_err(Error::from),
+      Err(_) => {
+        tracing::error!(command, timeout_ms = self.timeout.as_millis() as u64, "Redis command timed out");
+        Err(Error::ServiceUnavailable("Cache is unavailable.".to_string()))
+      }
+    }
   }
 }
 
 #[async_trait]
 impl TokenRepository for TokenRepositoryImpl {
-  async fn get_token(&self, key: String) -> Result<String, Error> {
-    return self.redis
-      .lock()
-      .await
-      .get(key)
-      .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()));
+  #[tracing::instrument(name = "TokenRepository::get_token", level = "debug", skip_all)]
+  async fn get_token(&self, key: String) -> Result<Option<String>, Error> {
+    self.run("get", |mut redis| async move { redis.get(key).await }).await
   }
 
-  async fn save_token(&self, key: String, token: String, exp: Duration) -> Result<bool, Error> {
-    self.redis
-      .lock()
-      .await
-      .set_ex(key, token, exp.num_seconds() as u64)
-      .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+  #[tracing::instrument(name = "TokenRepository::take_token", level = "debug", skip_all)]
+  async fn take_token(&self, key: String) -> Result<Option<String>, Error> {
+    self.run("getdel", |mut redis| async move { redis.get_del(key).await }).await
   }
 
+  #[tracing::instrument(name = "TokenRepository::save_token", level = "debug", skip_all)]
+  async fn save_token(&self, key: String, token: String, exp: chrono::Duration) -> Result<bool, Error> {
+    let seconds = exp.num_seconds() as u64;
+
+    self.run("setex", |mut redis| async move { redis.set_ex(key, token, seconds).await }).await
+  }
+
+  #[tracing::instrument(name = "TokenRepository::delete_token", level = "debug", skip_all)]
   async fn delete_token(&self, key: String) -> Result<bool, Error> {
-    self.redis
-      .lock()
-      .await
-      .del(key)
-      .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
-  }
-}
\ No newline at end of file
+    self.run("del", |mut redis| async move { redis.del(key).await }).await
+  }
+
+  /// Walks the keyspace page by page, deleting the matches of each page before fetching the next.
+  #[tracing::instrument(name = "TokenRepository::delete_tokens_with_prefix", level = "debug", skip_all)]
+  async fn delete_tokens_with_prefix(&self, prefix: String) -> Result<usize, Error> {
+    let pattern = format!("{}*", prefix);
+    let mut cursor: u64 = 0;
+    let mut deleted = 0;
+
+    loop {
+      let (next, keys): (u64, Vec<String>) = self.run("scan", |mut redis| {
+        let pattern = pattern.clone();
+        async move {
+          redis::cmd("SCAN").arg(cursor).arg("MATCH").arg(pattern).arg("COUNT").arg(SCAN_COUNT)
+            .query_async(&mut redis)
+            .await
+        }
+      }).await?;
+
+      if !keys.is_empty() {
+        let count: usize = self.run("del", |mut redis| async move { redis.del(keys).await }).await?;
+        deleted += count;
+      }
+
+      if next == 0 {
+        return Ok(deleted);
+      }
+      cursor = next;
+    }
+  }
+
+  #[tracing::instrument(name = "TokenRepository::ping", level = "debug", skip_all)]
+  async fn ping(&self) -> Result<(), Error> {
+    self.run("ping", |mut redis| async move {
+      redis::cmd("PING").query_async::<_, String>(&mut redis).await.map(|_| ())
+    }).await
+  }
+}
@@ -31,67 +31,80 @@ impl UserRepositoryImpl {
 
 #[async_trait]
 impl UserRepository for UserRepositoryImpl {
+  #[tracing::instrument(name = "UserRepository::all", level = "debug", skip_all)]
   async fn all(&self) -> Result<Vec<User>, Error> {
     let conn = self.pool.deref();
     query_as::<_, User>("SELECT * FROM users")
       .fetch_all(conn)
       .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map_err(Error::from)
   }
 
+  #[tracing::instrument(name = "UserRepository::get_by_id", level = "debug", skip_all, fields(id = %id))]
   async fn get_by_id(&self, id: Uuid) -> Result<Option<User>, Error> {
     let conn = self.pool.deref();
     query_as::<_, User>("SELECT * FROM users WHERE id = $1")
-      .bind(&id)
+      .bind(id)
       .fetch_optional(conn)
       .await
-      .map_err(|_| Error::NotFound("Not found.".to_string()))
+      .map_err(Error::from)
   }
 
+  #[tracing::instrument(name = "UserRepository::get_by_email", level = "debug", skip_all)]
   async fn get_by_email(&self, email: String) -> Result<Option<User>, Error> {
     let conn = self.pool.deref();
     query_as::<_, User>("SELECT * FROM users WHERE email = $1")
       .bind(&email)
       .fetch_optional(conn)
       .await
-      .map_err(|_| Error::NotFound("Not found.".to_string()))
+      .map_err(Error::from)
   }
 
+  #[tracing::instrument(name = "UserRepository::create", level = "debug", skip_all)]
   async fn create(&self, user: CreateUser) -> Result<User, Error> {
     let conn = self.pool.deref();
     query_as::<_, User>(
-      "INSERT INTO users (email, username, password) VALUES ($1, $2, $3) RETURNING *",
+      "INSERT INTO users (email, username, password, role) VALUES ($1, $2, $3, $4) RETURNING *",
     )
       .bind(&user.email)
       .bind(&user.username)
       .bind(&user.password)
+      .bind(&user.role)
       .fetch_one(conn)
       .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map_err(Error::from)
   }
 
+  #[tracing::instrument(name = "UserRepository::update", level = "debug", skip_all, fields(id = %id))]
   async fn update(&self, id: Uuid, user: User) -> Result<User, Error> {
     let conn = self.pool.deref();
     query_as::<_, User>(
-      "UPDATE users SET email = $1, username = $2, password = $3 WHERE id = $4 RETURNING *",
+      "UPDATE users SET email = $1, username = $2, password = $3, role = $4 WHERE id = $5 RETURNING *",
     )
       .bind(&user.email)
       .bind(&user.username)
       .bind(&user.password)
-      .bind(&id)
+      .bind(&user.role)
+      .bind(id)
       .fetch_one(conn)
       .await
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map_err(Error::from)
   }
 
+  #[tracing::instrument(name = "UserRepository::delete", level = "debug", skip_all, fields(id = %id))]
   async fn delete(&self, id: Uuid) -> Result<(), Error> {
     let conn = self.pool.deref();
-    query("DELETE FROM users WHERE id = $1")
-      .bind(&id)
+    let result = query("DELETE FROM users WHERE id = $1")
+      .bind(id)
       .execute(conn)
       .await
-      .map(|_| ())
-      .map_err(|_| Error::InternalServerError("Something went wrong.".to_string()))
+      .map_err(Error::from)?;
+
+    if result.rows_affected() == 0 {
+      return Err(Error::NotFound("Not found.".to_string()));
+    }
+
+    Ok(())
 
   }
 }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use uuid::Uuid;
 
+use crate::config::metrics::Metrics;
 use crate::config::settings::Settings;
 use crate::dtos::response::tokens_response_dto::TokensResponseDto;
 use crate::enums::error::Error;
@@ -23,14 +24,44 @@ pub struct AuthServiceImpl {
   user_service: Arc<dyn UserService>,
   token_service: Arc<dyn TokenService>,
   settings: Arc<Settings>,
+  metrics: Arc<Metrics>,
 }
 
 impl AuthServiceImpl {
-  pub fn new(user_service: Arc<dyn UserService>, token_service: Arc<dyn TokenService>, settings: Arc<Settings>) -> Self {
+  pub fn new(
+    user_service: Arc<dyn UserService>,
+    token_service: Arc<dyn TokenService>,
+    settings: Arc<Settings>,
+    metrics: Arc<Metrics>,
+  ) -> Self {
     AuthServiceImpl {
       user_service,
       token_service,
       settings,
+      metrics,
+    }
+  }
+
+  async fn authenticate(&self, user: User) -> Result<TokensResponseDto, Error> {
+    let db_user = self.user_service.get_by_email(user.email.clone()).await;
+
+    match db_user {
+      Ok(Some(u)) => {
+        self.check_user(&user, &u)?;
+
+        let user_id = u.id;
+        let token_id = Uuid::new_v4();
+
+        let tokens = self.generate_tokens(&user_id, &token_id, &u.role)?;
+
+        if self.save_tokens(&user_id, &token_id, &tokens).await.is_err() {
+          return Err(Error::InternalServerError("Error while saving tokens.".to_string()));
+        }
+
+        Ok(tokens)
+      }
+      Ok(None) => Err(Error::Unauthorized("Invalid email or password.".to_string())),
+      Err(error) => Err(error),
     }
   }
 
@@ -40,54 +71,30 @@ impl AuthServiceImpl {
     let hash = db_user.password.clone();
 
     if db_user.email != *email || !check_password_hash(password, hash) {
-      return Err(Error::InternalServerError("Authentication failed.".to_string()));
+      return Err(Error::Unauthorized("Invalid email or password.".to_string()));
     }
 
-    return Ok(());
+    Ok(())
   }
 
-  fn generate_tokens(&self, user_id: &Uuid, token_id: &Uuid) -> Result<TokensResponseDto, Error> {
-    let result = self.token_service.generate_access_token(user_id.clone(), token_id.clone());
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
-
-    let access_token = result.unwrap();
-
-    let result = self.token_service.generate_refresh_token(user_id.clone(), token_id.clone());
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
-
-    let refresh_token = result.unwrap();
+  /// The role is carried in both tokens, so a refresh keeps it until the user signs in again.
+  fn generate_tokens(&self, user_id: &Uuid, token_id: &Uuid, role: &str) -> Result<TokensResponseDto, Error> {
+    let access_token = self.token_service.generate_access_token(*user_id, *token_id, role)?;
+    let refresh_token = self.token_service.generate_refresh_token(*user_id, *token_id, role)?;
 
     Ok(TokensResponseDto { access_token, refresh_token })
   }
 
   async fn save_tokens(&self, user_id: &Uuid, token_id: &Uuid, tokens: &TokensResponseDto) -> Result<(), Error> {
-    let result = self.token_service.save_access_token(user_id.clone(), token_id.clone(), tokens.access_token.clone()).await;
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
-
-    let result = self.token_service.save_refresh_token(user_id.clone(), token_id.clone(), tokens.refresh_token.clone()).await;
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
+    self.token_service.save_access_token(*user_id, *token_id, tokens.access_token.clone()).await?;
+    self.token_service.save_refresh_token(*user_id, *token_id, tokens.refresh_token.clone()).await?;
 
     Ok(())
   }
 
   async fn delete_tokens(&self, user_id: &Uuid, token_id: &Uuid) -> Result<(), Error> {
-    let result = self.token_service.delete_access_tokens(user_id.clone(), token_id.clone()).await;
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
-
-    let result = self.token_service.delete_refresh_tokens(user_id.clone(), token_id.clone()).await;
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
+    self.token_service.delete_access_tokens(*user_id, *token_id).await?;
+    self.token_service.delete_refresh_tokens(*user_id, *token_id).await?;
 
     Ok(())
   }
@@ -95,55 +102,27 @@ impl AuthServiceImpl {
 
 #[async_trait]
 impl AuthService for AuthServiceImpl {
+  #[tracing::instrument(name = "AuthService::login", level = "debug", skip_all)]
   async fn login(&self, user: User) -> Result<TokensResponseDto, Error> {
-    let db_user = self.user_service.get_by_email(user.email.clone()).await;
-
-    match db_user {
-      Ok(Some(u)) => {
-        let result = self.check_user(&user, &u);
-        if result.is_err() {
-          return Err(result.unwrap_err());
-        }
-
-        let user_id = u.id;
-        let token_id = Uuid::new_v4();
-
-        let result = self.generate_tokens(&user_id, &token_id);
-        if result.is_err() {
-          return Err(result.unwrap_err());
-        }
-
-        let tokens = result.unwrap();
+    let result = self.authenticate(user).await;
 
-        let result = self.save_tokens(&user_id, &token_id, &tokens).await;
-        if result.is_err() {
-          return Err(Error::InternalServerError("Error while saving tokens.".to_string()));
-        }
+    let outcome = match &result {
+      Ok(_) => "success",
+      Err(Error::Unauthorized(_)) => "invalid_credentials",
+      Err(_) => "error",
+    };
+    self.metrics.logins_total.with_label_values(&[outcome]).inc();
 
-        Ok(tokens)
-      }
-      Err(_) => Err(Error::InternalServerError("User not found.".to_string())),
-      _ => Err(Error::InternalServerError("An error occurred.".to_string()))
-    }
+    result
   }
 
+  #[tracing::instrument(name = "AuthService::logout", level = "debug", skip_all)]
   async fn logout(&self, auth_header: String) -> Result<(), Error> {
-    let result = get_bearer_token(auth_header);
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
-
-    let token_string = result.unwrap();
-
-    let result = parse_jwt_token(token_string, self.settings.access_secret.clone());
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
-
-    let claims = result.unwrap();
+    let token_string = get_bearer_token(auth_header)?;
+    let claims = parse_jwt_token(token_string, self.settings.access_secret.clone())?;
 
     if claims.is_refresh {
-      return Err(Error::InternalServerError("Used refresh token for logout.".to_string()));
+      return Err(Error::Unauthorized("Used refresh token for logout.".to_string()));
     }
 
     match self.delete_tokens(&claims.sub, &claims.jti).await {
@@ -152,35 +131,30 @@ impl AuthService for AuthServiceImpl {
     }
   }
 
+  #[tracing::instrument(name = "AuthService::refresh", level = "debug", skip_all)]
   async fn refresh(&self, token_string: String) -> Result<TokensResponseDto, Error> {
-    let result = parse_jwt_token(token_string, self.settings.refresh_secret.clone());
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
-
-    let claims = result.unwrap();
+    let claims = parse_jwt_token(token_string.clone(), self.settings.refresh_secret.clone())?;
 
     if !claims.is_refresh {
-      return Err(Error::InternalServerError("Must be refresh token.".to_string()));
+      return Err(Error::Unauthorized("Must be refresh token.".to_string()));
     }
 
     let user_id = &claims.sub;
-    let token_id = &claims.jti;
 
-    let result = self.delete_tokens(&user_id, &token_id).await;
-    if result.is_err() {
-      return Err(result.unwrap_err());
+    // Taking the stored refresh token consumes it, so of concurrent refreshes with the same token only
+    // one succeeds. It is also deleted on logout or when revoked.
+    if self.token_service.take_refresh_token(*user_id, claims.jti).await? != Some(token_string) {
+      return Err(Error::Unauthorized("Refresh token was revoked.".to_string()));
     }
 
-    let result = self.generate_tokens(&user_id, &token_id);
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
+    self.token_service.delete_access_tokens(*user_id, claims.jti).await?;
 
-    let tokens = result.unwrap();
-    match self.save_tokens(&user_id, &token_id, &tokens).await {
+    // A new id keeps the new tokens distinct from the old ones, even when issued within the same second.
+    let token_id = &Uuid::new_v4();
+    let tokens = self.generate_tokens(user_id, token_id, &claims.role)?;
+    match self.save_tokens(user_id, token_id, &tokens).await {
       Ok(_) => Ok(tokens),
-      Err(_) => Err(Error::InternalServerError("Error while saving tokens.".to_string()))
+      Err(error) => Err(Error::internal("Error while saving tokens.", error))
     }
   }
 }
\ No newline at end of file
@@ -4,57 +4,116 @@ use std::vec::Vec;
 use async_trait::async_trait;
 use uuid::Uuid;
 
+use crate::config::metrics::Metrics;
 use crate::enums::error::Error;
 use crate::models::comment::Comment;
+use crate::models::comment_patch::CommentPatch;
+use crate::models::token_claims::TokenClaims;
 use crate::repository::comment_repository::CommentRepository;
+use crate::traits::entity_tag::check_if_match;
+use crate::utils::permission_util::check_owner;
 
 #[async_trait]
 pub trait CommentService: Send + Sync {
   async fn get_all(&self) -> Result<Vec<Comment>, Error>;
   async fn get_all_for_post(&self, post_id: Uuid) -> Result<Vec<Comment>, Error>;
+  async fn get_all_for_user(&self, user_id: Uuid) -> Result<Vec<Comment>, Error>;
   async fn get_one(&self, id: Uuid) -> Result<Comment, Error>;
   async fn create(&self, comment: Comment) -> Result<Comment, Error>;
-  async fn update(&self, comment: Comment) -> Result<Comment, Error>;
+  async fn update(&self, claims: &TokenClaims, comment: Comment, if_match: Option<String>) -> Result<Comment, Error>;
+  async fn patch(
+    &self,
+    claims: &TokenClaims,
+    post_id: Uuid,
+    id: Uuid,
+    patch: CommentPatch,
+    if_match: Option<String>,
+  ) -> Result<Comment, Error>;
 }
 
 #[derive(Clone)]
 pub struct CommentServiceImpl {
   repo: Arc<dyn CommentRepository>,
+  metrics: Arc<Metrics>,
 }
 
 impl CommentServiceImpl {
-  pub fn new(repo: Arc<dyn CommentRepository>) -> Self {
-    CommentServiceImpl { repo }
+  pub fn new(repo: Arc<dyn CommentRepository>, metrics: Arc<Metrics>) -> Self {
+    CommentServiceImpl { repo, metrics }
+  }
+
+  async fn get_one_for_post(&self, post_id: Uuid, id: Uuid) -> Result<Comment, Error> {
+    let comment = self.repo.get_one(id).await?;
+    if comment.post_id != post_id {
+      return Err(Error::NotFound("Not found.".to_string()));
+    }
+
+    Ok(comment)
   }
 }
 
 #[async_trait]
 impl CommentService for CommentServiceImpl {
+  #[tracing::instrument(name = "CommentService::get_all", level = "debug", skip_all)]
   async fn get_all(&self) -> Result<Vec<Comment>, Error> {
     self.repo.get_all().await
   }
 
+  #[tracing::instrument(
+    name = "CommentService::get_all_for_post", level = "debug", skip_all, fields(post_id = %post_id),
+  )]
   async fn get_all_for_post(&self, post_id: Uuid) -> Result<Vec<Comment>, Error> {
     self.repo.get_all_for_post(post_id).await
   }
 
+  #[tracing::instrument(
+    name = "CommentService::get_all_for_user", level = "debug", skip_all, fields(user_id = %user_id),
+  )]
+  async fn get_all_for_user(&self, user_id: Uuid) -> Result<Vec<Comment>, Error> {
+    self.repo.get_all_for_user(user_id).await
+  }
+
+  #[tracing::instrument(name = "CommentService::get_one", level = "debug", skip_all, fields(id = %id))]
   async fn get_one(&self, id: uuid::Uuid) -> Result<Comment, Error> {
     self.repo.get_one(id).await
   }
 
+  #[tracing::instrument(name = "CommentService::create", level = "debug", skip_all)]
   async fn create(&self, comment: Comment) -> Result<Comment, Error> {
-    self.repo.create(comment).await
+    let comment = self.repo.create(comment).await?;
+    self.metrics.comments_created_total.inc();
+
+    Ok(comment)
   }
 
-  async fn update(&self, comment: Comment) -> Result<Comment, Error> {
-    let result = self.get_one(comment.id).await;
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
+  #[tracing::instrument(name = "CommentService::update", level = "debug", skip_all, fields(id = %comment.id))]
+  async fn update(&self, claims: &TokenClaims, comment: Comment, if_match: Option<String>) -> Result<Comment, Error> {
+    let mut old_comment = self.get_one_for_post(comment.post_id, comment.id).await?;
+    check_owner(claims, old_comment.user_id)?;
+    check_if_match(&old_comment, if_match.as_deref())?;
 
-    let mut old_comment = result.unwrap();
     old_comment.body = comment.body;
 
     self.repo.update(old_comment).await
   }
+
+  #[tracing::instrument(name = "CommentService::patch", level = "debug", skip_all, fields(id = %id))]
+  async fn patch(
+    &self,
+    claims: &TokenClaims,
+    post_id: Uuid,
+    id: Uuid,
+    patch: CommentPatch,
+    if_match: Option<String>,
+  ) -> Result<Comment, Error> {
+    let mut comment = self.get_one_for_post(post_id, id).await?;
+    check_owner(claims, comment.user_id)?;
+    check_if_match(&comment, if_match.as_deref())?;
+
+    if let Some(body) = patch.body {
+      comment.body = body;
+    }
+
+    self.repo.update(comment).await
+  }
 }
\ No newline at end of file
@@ -0,0 +1,219 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use chrono::{Duration, Utc};
+use tokio::sync::Semaphore;
+use tokio_util::task::TaskTracker;
+use tracing::Instrument;
+use uuid::Uuid;
+use zip::ZipWriter;
+
+use crate::enums::error::Error;
+use crate::enums::export_status::ExportStatus;
+use crate::mappers::comment_mapper::from_comment_to_dto;
+use crate::mappers::post_mapper::from_post_to_dto;
+use crate::mappers::user_mapper::from_user_to_dto;
+use crate::models::export::Export;
+use crate::models::post::Post;
+use crate::repository::export_repository::ExportRepository;
+use crate::services::comment_service::CommentService;
+use crate::services::post_service::PostService;
+use crate::services::store_service::StoreService;
+use crate::services::user_service::UserService;
+use crate::utils::archive_util::{add_file, add_json};
+use crate::utils::file_util::get_extension_for_content_type;
+use crate::utils::store_key_util::get_export_key;
+
+const DOWNLOAD_LINK_TTL_MINUTES: i64 = 15;
+// Archives are built in memory, so only a few are built at once and the other requests wait.
+const MAX_CONCURRENT_BUILDS: usize = 2;
+
+#[async_trait]
+pub trait ExportService: Send + Sync {
+  async fn request(&self, user_id: Uuid) -> Result<Export, Error>;
+  async fn get_one(&self, user_id: Uuid, id: Uuid) -> Result<Export, Error>;
+  async fn download(&self, id: Uuid, token: Uuid) -> Result<Bytes, Error>;
+  /// Fails the exports left pending by a previous run, whose builds were lost with it.
+  async fn fail_interrupted(&self) -> Result<u64, Error>;
+  /// Waits for the archives being built, on shutdown.
+  async fn drain(&self);
+}
+
+#[derive(Clone)]
+pub struct ExportServiceImpl {
+  repo: Arc<dyn ExportRepository>,
+  user_service: Arc<dyn UserService>,
+  post_service: Arc<dyn PostService>,
+  comment_service: Arc<dyn CommentService>,
+  store_service: Arc<dyn StoreService>,
+  builds: TaskTracker,
+  build_permits: Arc<Semaphore>,
+}
+
+impl ExportServiceImpl {
+  pub fn new(
+    repo: Arc<dyn ExportRepository>,
+    user_service: Arc<dyn UserService>,
+    post_service: Arc<dyn PostService>,
+    comment_service: Arc<dyn CommentService>,
+    store_service: Arc<dyn StoreService>,
+  ) -> Self {
+    Self {
+      repo,
+      user_service,
+      post_service,
+      comment_service,
+      store_service,
+      builds: TaskTracker::new(),
+      build_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_BUILDS)),
+    }
+  }
+
+  async fn build(&self, mut export: Export) {
+    let Ok(_permit) = self.build_permits.acquire().await else { return };
+
+    let object_id = Uuid::new_v4();
+    let result = match self.create_archive(export.user_id).await {
+      Ok(archive) => self.store_service
+        .save_with_key(get_export_key(object_id), archive, "application/zip".to_string())
+        .await,
+      Err(error) => Err(error),
+    };
+
+    match result {
+      Ok(()) => {
+        export.status = ExportStatus::Ready.as_str().to_string();
+        export.object_id = Some(object_id);
+        export.expires_at = Some(Utc::now() + Duration::minutes(DOWNLOAD_LINK_TTL_MINUTES));
+      }
+      Err(error) => {
+        tracing::error!(export_id = %export.id, error = %error, "Building export failed");
+        export.status = ExportStatus::Failed.as_str().to_string();
+      }
+    }
+
+    if let Err(error) = self.repo.update(export).await {
+      tracing::error!(error = %error, "Saving export status failed");
+    }
+  }
+
+  async fn create_archive(&self, user_id: Uuid) -> Result<Bytes, Error> {
+    let user = self.user_service.get_one(user_id)
+      .await?
+      .ok_or_else(|| Error::NotFound("User not found.".to_string()))?;
+    let posts = self.post_service.get_all_for_user(user_id).await?;
+    let comments = self.comment_service.get_all_for_user(user_id).await?;
+
+    let mut images: Vec<(String, Bytes)> = Vec::new();
+    for post in &posts {
+      let object_ids = (post.image_id != Uuid::nil())
+        .then_some(post.image_id)
+        .into_iter()
+        .chain(post.media.iter().map(|media| media.object_id));
+
+      for object_id in object_ids {
+        let (content, content_type) = self.store_service.get(object_id.to_string()).await?;
+        let extension = get_extension_for_content_type(content_type.as_str());
+        images.push((format!("images/{}.{}", object_id, extension), content));
+      }
+    }
+
+    // Compressing is CPU bound, so it runs off the async workers.
+    tokio::task::spawn_blocking(move || {
+      let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+
+      add_json(&mut archive, "profile.json".to_string(), &from_user_to_dto(&user))?;
+
+      for post in &posts {
+        add_json(&mut archive, format!("posts/{}.json", post.id), &from_post_to_dto(post))?;
+        add_file(&mut archive, format!("posts/{}.md", post.id), Self::to_markdown(post).as_bytes())?;
+      }
+
+      for (name, content) in images {
+        add_file(&mut archive, name, &content)?;
+      }
+
+      let comments: Vec<_> = comments.iter().map(from_comment_to_dto).collect();
+      add_json(&mut archive, "comments.json".to_string(), &comments)?;
+
+      let cursor = archive.finish()
+        .map_err(|error| Error::internal("Error while creating archive.", error))?;
+
+      Ok(Bytes::from(cursor.into_inner()))
+    })
+      .await
+      .map_err(|error| Error::internal("Error while creating archive.", error))?
+  }
+
+  fn to_markdown(post: &Post) -> String {
+    format!(
+      "---\nid: {}\ncreatedAt: {}\nupdatedAt: {}\n---\n\n# {}\n\n{}\n",
+      post.id,
+      post.created_at.to_rfc3339(),
+      post.updated_at.to_rfc3339(),
+      post.title,
+      post.body
+    )
+  }
+}
+
+#[async_trait]
+impl ExportService for ExportServiceImpl {
+  #[tracing::instrument(name = "ExportService::request", level = "debug", skip_all, fields(user_id = %user_id))]
+  async fn request(&self, user_id: Uuid) -> Result<Export, Error> {
+    // A user has at most one pending export, further requests get that one until it is built.
+    let Some(export) = self.repo.create(user_id).await? else {
+      return self.repo.get_pending(user_id)
+        .await?
+        .ok_or_else(|| Error::Conflict("An export was requested concurrently.".to_string()));
+    };
+
+    let service = self.clone();
+    let pending = export.clone();
+    self.builds.spawn(async move { service.build(pending).await }.in_current_span());
+
+    Ok(export)
+  }
+
+  #[tracing::instrument(
+    name = "ExportService::get_one", level = "debug", skip_all, fields(user_id = %user_id, id = %id),
+  )]
+  async fn get_one(&self, user_id: Uuid, id: Uuid) -> Result<Export, Error> {
+    let export = self.repo.get_one(id).await?;
+    if export.user_id != user_id {
+      return Err(Error::NotFound("Not found.".to_string()));
+    }
+
+    Ok(export)
+  }
+
+  #[tracing::instrument(name = "ExportService::download", level = "debug", skip_all, fields(id = %id))]
+  async fn download(&self, id: Uuid, token: Uuid) -> Result<Bytes, Error> {
+    let export = self.repo.get_one(id).await?;
+    if export.download_token != token || export.status != ExportStatus::Ready.as_str() {
+      return Err(Error::NotFound("Not found.".to_string()));
+    }
+
+    let is_expired = export.expires_at.is_none_or(|expires_at| expires_at <= Utc::now());
+    let object_id = match export.object_id {
+      Some(object_id) if !is_expired => object_id,
+      _ => return Err(Error::Gone("Download link has expired.".to_string())),
+    };
+
+    let (content, _) = self.store_service.get(get_export_key(object_id)).await?;
+
+    Ok(content)
+  }
+
+  #[tracing::instrument(name = "ExportService::fail_interrupted", level = "debug", skip_all)]
+  async fn fail_interrupted(&self) -> Result<u64, Error> {
+    self.repo.fail_pending().await
+  }
+
+  async fn drain(&self) {
+    self.builds.close();
+    self.builds.wait().await;
+  }
+}
@@ -0,0 +1,84 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::enums::error::Error;
+use crate::enums::health_status::HealthStatus;
+use crate::models::health_report::{DependencyHealth, HealthReport};
+use crate::repository::health_repository::HealthRepository;
+use crate::repository::token_repository::TokenRepository;
+use crate::services::store_service::StoreService;
+
+// A dependency slower than this is reported as down rather than stalling the probe.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[async_trait]
+pub trait HealthService: Send + Sync {
+  /// Checks every dependency concurrently. Down when a critical one is down,
+  /// degraded when only non-critical ones are.
+  async fn ready(&self) -> HealthReport;
+}
+
+pub struct HealthServiceImpl {
+  health_repository: Arc<dyn HealthRepository>,
+  token_repository: Arc<dyn TokenRepository>,
+  store_service: Arc<dyn StoreService>,
+}
+
+impl HealthServiceImpl {
+  pub fn new(
+    health_repository: Arc<dyn HealthRepository>,
+    token_repository: Arc<dyn TokenRepository>,
+    store_service: Arc<dyn StoreService>,
+  ) -> Self {
+    HealthServiceImpl { health_repository, token_repository, store_service }
+  }
+}
+
+async fn check<F>(name: &str, critical: bool, ping: F) -> DependencyHealth
+  where
+    F: Future<Output = Result<(), Error>>,
+{
+  let started = Instant::now();
+  let error = match tokio::time::timeout(CHECK_TIMEOUT, ping).await {
+    Ok(Ok(())) => None,
+    Ok(Err(error)) => Some(error.message()),
+    Err(_) => Some(format!("No answer within {} ms.", CHECK_TIMEOUT.as_millis())),
+  };
+
+  DependencyHealth {
+    name: name.to_string(),
+    status: if error.is_none() { HealthStatus::Up } else { HealthStatus::Down },
+    critical,
+    latency_ms: started.elapsed().as_millis() as u64,
+    error,
+  }
+}
+
+#[async_trait]
+impl HealthService for HealthServiceImpl {
+  #[tracing::instrument(name = "HealthService::ready", level = "debug", skip_all)]
+  async fn ready(&self) -> HealthReport {
+    let (postgres, redis, store) = tokio::join!(
+      check("postgres", true, self.health_repository.ping()),
+      check("redis", true, self.token_repository.ping()),
+      check("store", false, self.store_service.ping()),
+    );
+    let dependencies = vec![postgres, redis, store];
+
+    let down: Vec<&DependencyHealth> = dependencies.iter()
+      .filter(|dependency| dependency.status == HealthStatus::Down)
+      .collect();
+    let status = if down.iter().any(|dependency| dependency.critical) {
+      HealthStatus::Down
+    } else if !down.is_empty() {
+      HealthStatus::Degraded
+    } else {
+      HealthStatus::Up
+    };
+
+    HealthReport { status, dependencies }
+  }
+}
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use image::ImageFormat;
+use uuid::Uuid;
+
+use crate::dtos::request::image_transform_request_dto::ImageTransformRequestDto;
+use crate::enums::error::Error;
+use crate::enums::image_fit::ImageFit;
+use crate::services::store_service::StoreService;
+use crate::utils::image_util::{get_transform_key, ImageTransform, transform_image};
+
+#[async_trait]
+pub trait ImageService: Send + Sync {
+  /// Returns the key of the transformed rendition, rendering and caching it first if needed.
+  async fn get_transformed(&self, id: String, request: ImageTransformRequestDto) -> Result<String, Error>;
+}
+
+#[derive(Clone)]
+pub struct ImageServiceImpl {
+  store_service: Arc<dyn StoreService>,
+  allowed_sizes: Vec<u32>,
+}
+
+impl ImageServiceImpl {
+  pub fn new(store_service: Arc<dyn StoreService>, allowed_sizes: Vec<u32>) -> Self {
+    ImageServiceImpl { store_service, allowed_sizes }
+  }
+
+  fn check_size(&self, size: Option<u32>) -> Result<Option<u32>, Error> {
+    match size {
+      Some(size) if !self.allowed_sizes.contains(&size) => Err(Error::BadRequest(
+        format!("Size must be one of {:?}.", self.allowed_sizes)
+      )),
+      _ => Ok(size),
+    }
+  }
+
+  fn to_transform(&self, request: ImageTransformRequestDto) -> Result<ImageTransform, Error> {
+    let fit = match request.fit {
+      Some(fit) => ImageFit::parse(fit.as_str())
+        .ok_or_else(|| Error::BadRequest("Fit must be one of contain, cover or fill.".to_string()))?,
+      None => ImageFit::Contain,
+    };
+
+    let format = match request.format.as_deref() {
+      Some("jpeg") | Some("jpg") => Some(ImageFormat::Jpeg),
+      Some("png") => Some(ImageFormat::Png),
+      Some("webp") => Some(ImageFormat::WebP),
+      Some(_) => return Err(Error::BadRequest("Format must be one of jpeg, png or webp.".to_string())),
+      None => None,
+    };
+
+    Ok(ImageTransform {
+      width: self.check_size(request.w)?,
+      height: self.check_size(request.h)?,
+      fit,
+      format,
+    })
+  }
+}
+
+#[async_trait]
+impl ImageService for ImageServiceImpl {
+  #[tracing::instrument(name = "ImageService::get_transformed", level = "debug", skip_all, fields(id = %id))]
+  async fn get_transformed(&self, id: String, request: ImageTransformRequestDto) -> Result<String, Error> {
+    let image_id = Uuid::parse_str(id.as_str())
+      .map_err(|_| Error::BadRequest("Only original images can be transformed.".to_string()))?;
+    let transform = self.to_transform(request)?;
+    let key = get_transform_key(image_id, &transform);
+
+    if self.store_service.head(key.clone()).await.is_ok() {
+      return Ok(key);
+    }
+
+    let (content, content_type) = self.store_service.get(image_id.to_string()).await?;
+    let (content, content_type) = tokio::task::spawn_blocking(move || transform_image(&content, &content_type, &transform))
+      .await
+      .map_err(|error| Error::internal("Error while transforming image.", error))??;
+
+    self.store_service.save_with_key(key.clone(), content, content_type).await?;
+
+    Ok(key)
+  }
+}
@@ -0,0 +1,237 @@
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use async_trait::async_trait;
+use axum::body::{Body, Bytes};
+use chrono::{DateTime, Utc};
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+use crate::enums::error::Error;
+use crate::models::store_entry::StoreEntry;
+use crate::models::store_object::StoreObject;
+use crate::services::store_service::StoreService;
+use crate::utils::http_util::format_http_date;
+
+/// Stores objects as files under `<root>/objects`, with their content type
+/// kept next to them under `<root>/meta`. A `/` in a key maps to a directory.
+#[derive(Clone)]
+pub struct LocalStoreServiceImpl {
+  root: PathBuf,
+}
+
+impl LocalStoreServiceImpl {
+  pub fn new(root: PathBuf) -> Self {
+    LocalStoreServiceImpl { root }
+  }
+
+  fn is_valid_key(key: &str) -> bool {
+    key.split('/').all(|segment| {
+      !segment.is_empty()
+        && !segment.starts_with('.')
+        && segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
+    })
+  }
+
+  fn object_path(&self, key: &str) -> Result<PathBuf, Error> {
+    if !Self::is_valid_key(key) {
+      return Err(Error::NotFound("File not found.".to_string()));
+    }
+
+    Ok(self.root.join("objects").join(key))
+  }
+
+  fn meta_path(&self, key: &str) -> PathBuf {
+    self.root.join("meta").join(key)
+  }
+
+  /// Writes to a temporary file in the same directory and renames it over `path`, so readers see
+  /// either the old or the new content but never a partial write.
+  async fn write_atomically(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, Uuid::new_v4()));
+
+    let result = match fs::write(&temp_path, content).await {
+      Ok(()) => fs::rename(&temp_path, path).await,
+      Err(error) => Err(error),
+    };
+    if result.is_err() {
+      let _ = fs::remove_file(&temp_path).await;
+    }
+
+    result
+  }
+}
+
+#[async_trait]
+impl StoreService for LocalStoreServiceImpl {
+  #[tracing::instrument(name = "StoreService::head", level = "debug", skip_all, fields(key = %key))]
+  async fn head(&self, key: String) -> Result<StoreObject, Error> {
+    let metadata = fs::metadata(self.object_path(key.as_str())?)
+      .await
+      .map_err(|_| Error::NotFound("File not found.".to_string()))?;
+
+    let content_type = fs::read_to_string(self.meta_path(key.as_str()))
+      .await
+      .unwrap_or_else(|_| "application/octet-stream".to_string());
+
+    let modified = metadata.modified().ok();
+    let e_tag = modified
+      .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+      .map(|modified| format!("\"{:x}-{:x}\"", metadata.len(), modified.as_nanos()));
+
+    Ok(StoreObject {
+      key,
+      content_type,
+      content_length: metadata.len(),
+      e_tag,
+      last_modified: modified.map(|modified| format_http_date(DateTime::<Utc>::from(modified))),
+    })
+  }
+
+  #[tracing::instrument(name = "StoreService::get", level = "debug", skip_all, fields(key = %key))]
+  async fn get(&self, key: String) -> Result<(Bytes, String), Error> {
+    let object = self.head(key).await?;
+    let content = fs::read(self.object_path(object.key.as_str())?)
+      .await
+      .map_err(|_| Error::NotFound("File not found.".to_string()))?;
+
+    Ok((Bytes::from(content), object.content_type))
+  }
+
+  #[tracing::instrument(name = "StoreService::stream", level = "debug", skip_all, fields(key = %key))]
+  async fn stream(&self, key: String, range: Option<(u64, u64)>) -> Result<Body, Error> {
+    let mut file = fs::File::open(self.object_path(key.as_str())?)
+      .await
+      .map_err(|_| Error::NotFound("File not found.".to_string()))?;
+
+    match range {
+      Some((start, end)) => {
+        file.seek(SeekFrom::Start(start))
+          .await
+          .map_err(|error| Error::internal("Error reading file.", error))?;
+
+        Ok(Body::from_stream(ReaderStream::new(file.take(end - start + 1))))
+      }
+      None => Ok(Body::from_stream(ReaderStream::new(file))),
+    }
+  }
+
+  #[tracing::instrument(name = "StoreService::save", level = "debug", skip_all)]
+  async fn save(&self, content: Bytes, content_type: String) -> Result<Uuid, Error> {
+    let id = Uuid::new_v4();
+
+    self.save_with_key(id.to_string(), content, content_type).await?;
+
+    Ok(id)
+  }
+
+  #[tracing::instrument(name = "StoreService::save_with_key", level = "debug", skip_all, fields(key = %key))]
+  async fn save_with_key(&self, key: String, content: Bytes, content_type: String) -> Result<(), Error> {
+    let path = self.object_path(key.as_str())?;
+    let meta_path = self.meta_path(key.as_str());
+
+    let result = async {
+      for path in [&path, &meta_path] {
+        if let Some(parent) = path.parent() {
+          fs::create_dir_all(parent).await?;
+        }
+      }
+      // Object first, so the metadata never describes content that is not there yet.
+      Self::write_atomically(&path, &content).await?;
+      Self::write_atomically(&meta_path, content_type.as_bytes()).await
+    }.await;
+
+    if let Err(error) = result {
+      return Err(Error::internal("Error saving file.", error));
+    }
+
+    Ok(())
+  }
+
+  #[tracing::instrument(name = "StoreService::delete", level = "debug", skip_all, fields(key = %key))]
+  async fn delete(&self, key: String) -> Result<(), Error> {
+    let path = self.object_path(key.as_str())?;
+
+    for path in [path, self.meta_path(key.as_str())] {
+      match fs::remove_file(path).await {
+        Ok(_) => {}
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+        Err(error) => return Err(Error::internal("Error deleting file.", error)),
+      }
+    }
+
+    Ok(())
+  }
+
+  #[tracing::instrument(name = "StoreService::presign_put", level = "debug", skip_all)]
+  async fn presign_put(
+    &self,
+    _key: String,
+    _content_type: String,
+    _content_length: u64,
+    _expiry_secs: u32,
+  ) -> Result<String, Error> {
+    Err(Error::BadRequest("Direct uploads are not supported by this store backend.".to_string()))
+  }
+
+  #[tracing::instrument(name = "StoreService::list", level = "debug", skip_all, fields(prefix = %prefix))]
+  async fn list(&self, prefix: String) -> Result<Vec<StoreEntry>, Error> {
+    let objects = self.root.join("objects");
+    let mut directories = vec![objects.clone()];
+    let mut entries: Vec<StoreEntry> = Vec::new();
+
+    while let Some(path) = directories.pop() {
+      let mut directory = match fs::read_dir(&path).await {
+        Ok(directory) => directory,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+        Err(error) => return Err(Error::internal("Error listing files.", error)),
+      };
+
+      while let Some(entry) = directory.next_entry()
+        .await
+        .map_err(|error| Error::internal("Error listing files.", error))? {
+        let metadata = entry.metadata()
+          .await
+          .map_err(|error| Error::internal("Error listing files.", error))?;
+        if metadata.is_dir() {
+          directories.push(entry.path());
+          continue;
+        }
+
+        // Temporary files of writes in progress.
+        if entry.file_name().to_string_lossy().starts_with('.') {
+          continue;
+        }
+
+        let modified = metadata.modified()
+          .map_err(|error| Error::internal("Error listing files.", error))?;
+        let Ok(key) = entry.path().strip_prefix(&objects).map(|key| key.to_string_lossy().into_owned()) else {
+          continue;
+        };
+        if !key.starts_with(prefix.as_str()) {
+          continue;
+        }
+
+        entries.push(StoreEntry {
+          key,
+          size: metadata.len(),
+          last_modified: DateTime::<Utc>::from(modified),
+        });
+      }
+    }
+
+    Ok(entries)
+  }
+
+  #[tracing::instrument(name = "StoreService::ping", level = "debug", skip_all)]
+  async fn ping(&self) -> Result<(), Error> {
+    // Objects are written lazily, so a missing directory only matters if it cannot be created.
+    fs::create_dir_all(self.root.join("objects"))
+      .await
+      .map_err(|_| Error::ServiceUnavailable("Store directory is not writable.".to_string()))
+  }
+}
\ No newline at end of file
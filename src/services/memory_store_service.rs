@@ -0,0 +1,141 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use axum::body::{Body, Bytes};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::enums::error::Error;
+use crate::models::store_entry::StoreEntry;
+use crate::models::store_object::StoreObject;
+use crate::services::store_service::StoreService;
+use crate::utils::http_util::format_http_date;
+
+#[derive(Clone)]
+struct MemoryObject {
+  content: Bytes,
+  content_type: String,
+  e_tag: String,
+  last_modified: DateTime<Utc>,
+}
+
+/// Keeps objects in process memory. Everything is lost on restart, so it is
+/// only meant for development and tests.
+#[derive(Clone, Default)]
+pub struct MemoryStoreServiceImpl {
+  objects: Arc<RwLock<HashMap<String, MemoryObject>>>,
+}
+
+impl MemoryStoreServiceImpl {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn find(&self, key: &str) -> Result<MemoryObject, Error> {
+    self.objects
+      .read()
+      .unwrap()
+      .get(key)
+      .cloned()
+      .ok_or_else(|| Error::NotFound("File not found.".to_string()))
+  }
+}
+
+#[async_trait]
+impl StoreService for MemoryStoreServiceImpl {
+  #[tracing::instrument(name = "StoreService::head", level = "debug", skip_all, fields(key = %key))]
+  async fn head(&self, key: String) -> Result<StoreObject, Error> {
+    let object = self.find(key.as_str())?;
+
+    Ok(StoreObject {
+      key,
+      content_type: object.content_type,
+      content_length: object.content.len() as u64,
+      e_tag: Some(object.e_tag),
+      last_modified: Some(format_http_date(object.last_modified)),
+    })
+  }
+
+  #[tracing::instrument(name = "StoreService::get", level = "debug", skip_all, fields(key = %key))]
+  async fn get(&self, key: String) -> Result<(Bytes, String), Error> {
+    let object = self.find(key.as_str())?;
+
+    Ok((object.content, object.content_type))
+  }
+
+  #[tracing::instrument(name = "StoreService::stream", level = "debug", skip_all, fields(key = %key))]
+  async fn stream(&self, key: String, range: Option<(u64, u64)>) -> Result<Body, Error> {
+    let object = self.find(key.as_str())?;
+
+    match range {
+      Some((start, end)) => Ok(Body::from(object.content.slice(start as usize..=end as usize))),
+      None => Ok(Body::from(object.content)),
+    }
+  }
+
+  #[tracing::instrument(name = "StoreService::save", level = "debug", skip_all)]
+  async fn save(&self, content: Bytes, content_type: String) -> Result<Uuid, Error> {
+    let id = Uuid::new_v4();
+
+    self.save_with_key(id.to_string(), content, content_type).await?;
+
+    Ok(id)
+  }
+
+  #[tracing::instrument(name = "StoreService::save_with_key", level = "debug", skip_all, fields(key = %key))]
+  async fn save_with_key(&self, key: String, content: Bytes, content_type: String) -> Result<(), Error> {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+
+    let object = MemoryObject {
+      content,
+      content_type,
+      e_tag: format!("\"{:x}\"", hasher.finish()),
+      last_modified: Utc::now(),
+    };
+
+    self.objects.write().unwrap().insert(key, object);
+
+    Ok(())
+  }
+
+  #[tracing::instrument(name = "StoreService::delete", level = "debug", skip_all, fields(key = %key))]
+  async fn delete(&self, key: String) -> Result<(), Error> {
+    self.objects.write().unwrap().remove(&key);
+
+    Ok(())
+  }
+
+  #[tracing::instrument(name = "StoreService::presign_put", level = "debug", skip_all)]
+  async fn presign_put(
+    &self,
+    _key: String,
+    _content_type: String,
+    _content_length: u64,
+    _expiry_secs: u32,
+  ) -> Result<String, Error> {
+    Err(Error::BadRequest("Direct uploads are not supported by this store backend.".to_string()))
+  }
+
+  #[tracing::instrument(name = "StoreService::list", level = "debug", skip_all, fields(prefix = %prefix))]
+  async fn list(&self, prefix: String) -> Result<Vec<StoreEntry>, Error> {
+    let objects = self.objects.read().unwrap();
+
+    Ok(objects.iter()
+      .filter(|(key, _)| key.starts_with(prefix.as_str()))
+      .map(|(key, object)| StoreEntry {
+        key: key.clone(),
+        size: object.content.len() as u64,
+        last_modified: object.last_modified,
+      })
+      .collect())
+  }
+
+  #[tracing::instrument(name = "StoreService::ping", level = "debug", skip_all)]
+  async fn ping(&self) -> Result<(), Error> {
+    Ok(())
+  }
+}
\ No newline at end of file
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::config::metrics::Metrics;
+use crate::enums::error::Error;
+use crate::repository::health_repository::HealthRepository;
+
+#[async_trait]
+pub trait MetricsService: Send + Sync {
+  /// Samples the gauges and renders every metric in the Prometheus text format.
+  async fn render(&self) -> Result<String, Error>;
+}
+
+pub struct MetricsServiceImpl {
+  metrics: Arc<Metrics>,
+  health_repository: Arc<dyn HealthRepository>,
+}
+
+impl MetricsServiceImpl {
+  pub fn new(metrics: Arc<Metrics>, health_repository: Arc<dyn HealthRepository>) -> Self {
+    MetricsServiceImpl { metrics, health_repository }
+  }
+}
+
+#[async_trait]
+impl MetricsService for MetricsServiceImpl {
+  #[tracing::instrument(name = "MetricsService::render", level = "debug", skip_all)]
+  async fn render(&self) -> Result<String, Error> {
+    let stats = self.health_repository.pool_stats().await;
+
+    let connections = &self.metrics.db_pool_connections;
+    connections.with_label_values(&["idle"]).set(stats.idle as i64);
+    connections.with_label_values(&["in_use"]).set(stats.size.saturating_sub(stats.idle) as i64);
+    self.metrics.db_pool_max_connections.set(stats.max_connections as i64);
+    // -1 when the probe timed out, the pool is exhausted.
+    self.metrics.db_pool_acquire_wait_seconds.set(stats.acquire_wait.map_or(-1.0, |wait| wait.as_secs_f64()));
+
+    self.metrics.render()
+  }
+}
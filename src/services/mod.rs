@@ -1,6 +1,14 @@
 pub mod auth_service;
 pub mod comment_service;
+pub mod export_service;
+pub mod health_service;
+pub mod image_service;
+pub mod local_store_service;
+pub mod metrics_service;
+pub mod memory_store_service;
+pub mod pending_deletion_service;
 pub mod post_service;
+pub mod storage_gc_service;
 pub mod store_service;
 pub mod token_service;
-pub mod user_service;
\ No newline at end of file
+pub mod user_service;
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::enums::error::Error;
+use crate::repository::pending_deletion_repository::PendingDeletionRepository;
+use crate::services::store_service::StoreService;
+
+// Number of queued deletions handled per pass.
+const BATCH_SIZE: i64 = 100;
+// Upper bound for the delay between two attempts at the same key.
+const MAX_RETRY_DELAY_SECS: i64 = 60 * 60;
+
+#[async_trait]
+pub trait PendingDeletionService: Send + Sync {
+  /// Queues the archives of expired exports, then removes due keys from the store and returns
+  /// how many were removed. Failed keys stay queued and are retried with exponential backoff.
+  async fn process(&self) -> Result<usize, Error>;
+  /// Wakes up the worker so freshly committed deletions are processed right away.
+  fn notify(&self);
+  async fn wait(&self, timeout: Duration);
+}
+
+#[derive(Clone)]
+pub struct PendingDeletionServiceImpl {
+  repo: Arc<dyn PendingDeletionRepository>,
+  store_service: Arc<dyn StoreService>,
+  notify: Arc<Notify>,
+}
+
+impl PendingDeletionServiceImpl {
+  pub fn new(repo: Arc<dyn PendingDeletionRepository>, store_service: Arc<dyn StoreService>) -> Self {
+    Self { repo, store_service, notify: Arc::new(Notify::new()) }
+  }
+}
+
+#[async_trait]
+impl PendingDeletionService for PendingDeletionServiceImpl {
+  #[tracing::instrument(name = "PendingDeletionService::process", level = "debug", skip_all)]
+  async fn process(&self) -> Result<usize, Error> {
+    let expired = self.repo.enqueue_expired_exports().await?;
+    if expired > 0 {
+      tracing::info!(expired, "Queued expired export archives");
+    }
+
+    let mut removed = 0;
+
+    for deletion in self.repo.get_due(BATCH_SIZE).await? {
+      match self.store_service.delete(deletion.key.clone()).await {
+        Ok(_) => {
+          self.repo.delete(deletion.id).await?;
+          removed += 1;
+        }
+        Err(error) => {
+          tracing::warn!(key = %deletion.key, attempts = deletion.attempts, error = %error, "Deleting object failed");
+          let delay = 2_i64.saturating_pow(deletion.attempts.max(0) as u32).min(MAX_RETRY_DELAY_SECS);
+          let next_attempt_at = Utc::now() + chrono::Duration::seconds(delay);
+
+          self.repo.reschedule(deletion.id, error.message(), next_attempt_at).await?;
+        }
+      }
+    }
+
+    Ok(removed)
+  }
+
+  fn notify(&self) {
+    self.notify.notify_one();
+  }
+
+  #[tracing::instrument(name = "PendingDeletionService::wait", level = "debug", skip_all)]
+  async fn wait(&self, timeout: Duration) {
+    let _ = tokio::time::timeout(timeout, self.notify.notified()).await;
+  }
+}
+
+/// Processes the queue every `interval` or when notified, until `shutdown` is cancelled.
+pub fn spawn_pending_deletions(
+  service: Arc<dyn PendingDeletionService>,
+  interval: Duration,
+  shutdown: CancellationToken,
+) -> JoinHandle<()> {
+  tokio::spawn(async move {
+    loop {
+      if let Err(error) = service.process().await {
+        tracing::error!(error = %error, "Processing pending deletions failed");
+      }
+
+      tokio::select! {
+        _ = shutdown.cancelled() => break,
+        _ = service.wait(interval) => {}
+      }
+    }
+  })
+}
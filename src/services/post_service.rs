@@ -1,106 +1,563 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::vec::Vec;
 
 use async_trait::async_trait;
 use axum::body::Bytes;
+use chrono::Utc;
 use uuid::Uuid;
 
+use crate::config::metrics::Metrics;
 use crate::enums::error::Error;
+use crate::models::image_upload::ImageUpload;
 use crate::models::post::Post;
+use crate::models::post_media::PostMedia;
+use crate::models::post_patch::PostPatch;
+use crate::models::token_claims::TokenClaims;
+use crate::repository::image_upload_repository::ImageUploadRepository;
+use crate::repository::post_media_repository::PostMediaRepository;
 use crate::repository::post_repository::PostRepository;
+use crate::services::pending_deletion_service::PendingDeletionService;
 use crate::services::store_service::StoreService;
+use crate::traits::entity_tag::check_if_match;
+use crate::utils::image_util::{
+  generate_variants, get_image_key, get_image_keys, get_transform_prefix, ImageVariants, prepare_image,
+};
+use crate::utils::media_util::get_media_references;
+use crate::utils::permission_util::check_owner;
+use crate::utils::store_key_util::get_upload_key;
+
+// Lifetime of the presigned URLs handed out for direct image uploads.
+const UPLOAD_URL_TTL_SECS: u32 = 15 * 60;
+// An upload can be confirmed for as long again after its URL expired.
+const UPLOAD_CONFIRM_TTL_SECS: i64 = 2 * UPLOAD_URL_TTL_SECS as i64;
 
 #[async_trait]
 pub trait PostService: Send + Sync {
   async fn get_all(&self) -> Result<Vec<Post>, Error>;
+  async fn get_all_for_user(&self, user_id: Uuid) -> Result<Vec<Post>, Error>;
   async fn get_one(&self, id: Uuid) -> Result<Post, Error>;
   async fn create(&self, post: Post) -> Result<Post, Error>;
-  async fn update(&self, post: Post) -> Result<Post, Error>;
-  async fn add_image(&self, id: Uuid, content: Bytes, content_type: String) -> Result<Post, Error>;
-  async fn delete(&self, id: Uuid) -> Result<(), Error>;
+  async fn update(&self, claims: &TokenClaims, post: Post, if_match: Option<String>) -> Result<Post, Error>;
+  async fn patch(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    patch: PostPatch,
+    if_match: Option<String>,
+  ) -> Result<Post, Error>;
+  async fn add_image(&self, claims: &TokenClaims, id: Uuid, content: Bytes) -> Result<Post, Error>;
+  async fn create_image_upload(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    content_type: String,
+    content_length: u64,
+    max_size: usize,
+  ) -> Result<ImageUpload, Error>;
+  async fn confirm_image_upload(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    key: Uuid,
+    max_size: usize,
+  ) -> Result<Post, Error>;
+  async fn delete(&self, claims: &TokenClaims, id: Uuid, if_match: Option<String>) -> Result<(), Error>;
+  async fn get_media(&self, id: Uuid) -> Result<Vec<PostMedia>, Error>;
+  async fn add_media(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    content: Bytes,
+    if_match: Option<String>,
+  ) -> Result<PostMedia, Error>;
+  async fn update_media(
+    &self,
+    claims: &TokenClaims,
+    media: PostMedia,
+    if_match: Option<String>,
+  ) -> Result<PostMedia, Error>;
+  async fn reorder_media(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    media_ids: Vec<Uuid>,
+    if_match: Option<String>,
+  ) -> Result<Vec<PostMedia>, Error>;
+  async fn delete_media(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    media_id: Uuid,
+    if_match: Option<String>,
+  ) -> Result<(), Error>;
+  /// Regenerates the width variants of post images from the stored originals, for posts
+  /// saved before variants existed or, with `all`, for every post with an image. Returns the
+  /// ids of the reindexed posts, a post whose image cannot be reindexed is logged and skipped.
+  async fn reindex_images(&self, all: bool) -> Result<Vec<Uuid>, Error>;
 }
 
 #[derive(Clone)]
 pub struct PostServiceImpl {
   repo: Arc<dyn PostRepository>,
+  media_repo: Arc<dyn PostMediaRepository>,
+  upload_repo: Arc<dyn ImageUploadRepository>,
   store_service: Arc<dyn StoreService>,
+  deletion_service: Arc<dyn PendingDeletionService>,
+  metrics: Arc<Metrics>,
 }
 
 impl PostServiceImpl {
-  pub fn new(repo: Arc<dyn PostRepository>, store_service: Arc<dyn StoreService>) -> Self {
-    Self { repo, store_service }
+  pub fn new(
+    repo: Arc<dyn PostRepository>,
+    media_repo: Arc<dyn PostMediaRepository>,
+    upload_repo: Arc<dyn ImageUploadRepository>,
+    store_service: Arc<dyn StoreService>,
+    deletion_service: Arc<dyn PendingDeletionService>,
+    metrics: Arc<Metrics>,
+  ) -> Self {
+    Self { repo, media_repo, upload_repo, store_service, deletion_service, metrics }
+  }
+
+  async fn with_media(&self, mut posts: Vec<Post>) -> Result<Vec<Post>, Error> {
+    let ids: Vec<Uuid> = posts.iter().map(|post| post.id).collect();
+    let mut media_by_post: HashMap<Uuid, Vec<PostMedia>> = HashMap::new();
+
+    for media in self.media_repo.get_all_for_posts(&ids).await? {
+      media_by_post.entry(media.post_id).or_default().push(media);
+    }
+
+    for post in posts.iter_mut() {
+      post.media = media_by_post.remove(&post.id).unwrap_or_default();
+    }
+
+    Ok(posts)
+  }
+
+  async fn with_media_one(&self, post: Post) -> Result<Post, Error> {
+    let mut posts = self.with_media(vec![post]).await?;
+
+    Ok(posts.remove(0))
+  }
+
+  /// Inline `media:<id>` references in a body must point at media of the post.
+  fn check_media_references(post: &Post) -> Result<(), Error> {
+    for reference in get_media_references(&post.body) {
+      if !post.media.iter().any(|media| media.id == reference) {
+        return Err(Error::BadRequest(format!("Unknown media {} referenced in body.", reference)));
+      }
+    }
+
+    Ok(())
+  }
+
+  async fn resize_image(image_id: Uuid, content: Bytes, content_type: String) -> Result<ImageVariants, Error> {
+    tokio::task::spawn_blocking(move || generate_variants(image_id, &content, &content_type))
+      .await
+      .map_err(|error| Error::internal("Error while resizing image.", error))?
+  }
+
+  async fn save_variants(&self, variants: &ImageVariants) -> Result<(), Error> {
+    for variant in &variants.variants {
+      self.store_service
+        .save_with_key(variant.key.clone(), variant.content.clone(), variant.content_type.clone())
+        .await?;
+    }
+
+    Ok(())
+  }
+
+  async fn save_image(&self, image_id: Uuid, content: Bytes, content_type: String) -> Result<ImageVariants, Error> {
+    let variants = Self::resize_image(image_id, content.clone(), content_type.clone()).await?;
+
+    self.store_service.save_with_key(get_image_key(image_id, None), content, content_type).await?;
+    self.save_variants(&variants).await?;
+
+    Ok(variants)
+  }
+
+  /// Variants that are no longer generated, e.g. because the list of widths changed, are deleted.
+  async fn reindex_image(&self, mut post: Post) -> Result<(), Error> {
+    let (content, content_type) = self.store_service.get(get_image_key(post.image_id, None)).await?;
+    let variants = Self::resize_image(post.image_id, content, content_type).await?;
+    self.save_variants(&variants).await?;
+
+    let widths: Vec<i32> = variants.widths.iter().map(|width| *width as i32).collect();
+    let keys = get_image_keys(post.image_id, &widths);
+    let deleted_keys = Self::get_post_image_keys(&post)
+      .into_iter()
+      .filter(|key| !keys.contains(key))
+      .collect();
+
+    post.image_width = variants.width as i32;
+    post.image_widths = widths;
+    post.image_content_type = variants.content_type;
+    self.repo.update(post, deleted_keys).await?;
+
+    Ok(())
+  }
+
+  /// `deleted_keys` are deleted along with the previous image of the post.
+  async fn attach_image(
+    &self,
+    id: Uuid,
+    image_id: Uuid,
+    variants: ImageVariants,
+    mut deleted_keys: Vec<String>,
+  ) -> Result<Post, Error> {
+    let mut post = self.get_one(id).await?;
+    deleted_keys.extend(Self::get_post_image_keys(&post));
+    deleted_keys.extend(self.get_transform_keys(post.image_id).await?);
+
+    post.image_id = image_id;
+    post.image_width = variants.width as i32;
+    post.image_widths = variants.widths.iter().map(|width| *width as i32).collect();
+    post.image_content_type = variants.content_type;
+
+    let post = self.repo.update(post, deleted_keys).await?;
+    self.deletion_service.notify();
+
+    self.with_media_one(post).await
+  }
+
+  /// Transformed renditions are cached on demand, so their keys are listed rather than derived. One
+  /// cached after the listing is left to the storage GC.
+  async fn get_transform_keys(&self, image_id: Uuid) -> Result<Vec<String>, Error> {
+    if image_id == Uuid::nil() {
+      return Ok(Vec::new());
+    }
+
+    let entries = self.store_service.list(get_transform_prefix(image_id)).await?;
+
+    Ok(entries.into_iter().map(|entry| entry.key).collect())
+  }
+
+  fn get_post_image_keys(post: &Post) -> Vec<String> {
+    if post.image_id == Uuid::nil() {
+      return Vec::new();
+    }
+
+    get_image_keys(post.image_id, &post.image_widths)
   }
 }
 
 #[async_trait]
 impl PostService for PostServiceImpl {
+  #[tracing::instrument(name = "PostService::get_all", level = "debug", skip_all)]
   async fn get_all(&self) -> Result<Vec<Post>, Error> {
-    self.repo.get_all().await
+    let posts = self.repo.get_all().await?;
+
+    self.with_media(posts).await
   }
 
+  #[tracing::instrument(name = "PostService::get_all_for_user", level = "debug", skip_all, fields(user_id = %user_id))]
+  async fn get_all_for_user(&self, user_id: Uuid) -> Result<Vec<Post>, Error> {
+    let posts = self.repo.get_all_for_user(user_id).await?;
+
+    self.with_media(posts).await
+  }
+
+  #[tracing::instrument(name = "PostService::get_one", level = "debug", skip_all, fields(id = %id))]
   async fn get_one(&self, id: Uuid) -> Result<Post, Error> {
-    self.repo.get_one(id).await
+    let post = self.repo.get_one(id).await?;
+
+    self.with_media_one(post).await
   }
 
+  #[tracing::instrument(name = "PostService::create", level = "debug", skip_all)]
   async fn create(&self, post: Post) -> Result<Post, Error> {
-    self.repo.create(post).await
+    // A new post has no media yet, so it cannot reference any.
+    Self::check_media_references(&post)?;
+
+    let post = self.repo.create(post).await?;
+    self.metrics.posts_created_total.inc();
+
+    Ok(post)
   }
 
-  async fn update(&self, post: Post) -> Result<Post, Error> {
-    let result = self.get_one(post.id).await;
-    if result.is_err() {
-      return Err(result.unwrap_err());
-    }
+  #[tracing::instrument(name = "PostService::update", level = "debug", skip_all, fields(id = %post.id))]
+  async fn update(&self, claims: &TokenClaims, post: Post, if_match: Option<String>) -> Result<Post, Error> {
+    let mut old_post = self.get_one(post.id).await?;
+    check_owner(claims, old_post.user_id)?;
+    check_if_match(&old_post, if_match.as_deref())?;
 
-    let mut old_post = result.unwrap();
     old_post.title = post.title;
     old_post.body = post.body;
 
-    self.repo.update(old_post).await
+    Self::check_media_references(&old_post)?;
+
+    let media = old_post.media.clone();
+    let mut post = self.repo.update(old_post, Vec::new()).await?;
+    post.media = media;
+
+    Ok(post)
+  }
+
+  #[tracing::instrument(name = "PostService::patch", level = "debug", skip_all, fields(id = %id))]
+  async fn patch(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    patch: PostPatch,
+    if_match: Option<String>,
+  ) -> Result<Post, Error> {
+    let mut post = self.get_one(id).await?;
+    check_owner(claims, post.user_id)?;
+    check_if_match(&post, if_match.as_deref())?;
+
+    if let Some(title) = patch.title {
+      post.title = title;
+    }
+    if let Some(body) = patch.body {
+      post.body = body;
+    }
+
+    Self::check_media_references(&post)?;
+
+    let mut deleted_keys = Vec::new();
+    if patch.clear_image {
+      deleted_keys = Self::get_post_image_keys(&post);
+      deleted_keys.extend(self.get_transform_keys(post.image_id).await?);
+
+      post.image_id = Uuid::nil();
+      post.image_width = 0;
+      post.image_widths = Vec::new();
+      post.image_content_type = String::new();
+    }
+
+    let media = std::mem::take(&mut post.media);
+    let mut post = self.repo.update(post, deleted_keys).await?;
+    post.media = media;
+    self.deletion_service.notify();
+
+    Ok(post)
+  }
+
+  #[tracing::instrument(name = "PostService::add_image", level = "debug", skip_all, fields(id = %id))]
+  async fn add_image(&self, claims: &TokenClaims, id: Uuid, content: Bytes) -> Result<Post, Error> {
+    check_owner(claims, self.get_one(id).await?.user_id)?;
+
+    let (content, content_type) = prepare_image(content)?;
+
+    let image_id = Uuid::new_v4();
+    let variants = self.save_image(image_id, content, content_type).await?;
+
+    self.attach_image(id, image_id, variants, Vec::new()).await
+  }
+
+  #[tracing::instrument(name = "PostService::create_image_upload", level = "debug", skip_all, fields(id = %id))]
+  async fn create_image_upload(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    content_type: String,
+    content_length: u64,
+    max_size: usize,
+  ) -> Result<ImageUpload, Error> {
+    if content_length > max_size as u64 {
+      return Err(Error::PayloadTooLarge(format!("File must not exceed {} bytes.", max_size)));
+    }
+
+    check_owner(claims, self.get_one(id).await?.user_id)?;
+
+    let expires_at = Utc::now() + chrono::Duration::seconds(UPLOAD_CONFIRM_TTL_SECS);
+    let key = self.upload_repo.create(id, expires_at).await?;
+    let url = self.store_service
+      .presign_put(get_upload_key(key), content_type, content_length, UPLOAD_URL_TTL_SECS)
+      .await?;
+
+    Ok(ImageUpload { key, url, expires_in: UPLOAD_URL_TTL_SECS })
   }
 
-  async fn add_image(&self, id: Uuid, content: Bytes, content_type: String) -> Result<Post, Error> {
-    let result = self.store_service.save(content, content_type).await;
-    if result.is_err() {
-      return Err(result.unwrap_err());
+  #[tracing::instrument(name = "PostService::confirm_image_upload", level = "debug", skip_all, fields(id = %id))]
+  async fn confirm_image_upload(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    key: Uuid,
+    max_size: usize,
+  ) -> Result<Post, Error> {
+    check_owner(claims, self.get_one(id).await?.user_id)?;
+
+    // Only keys issued for this post are accepted, any other object may belong to someone else.
+    if !self.upload_repo.is_pending(id, key).await? {
+      return Err(Error::NotFound("Unknown, expired or already confirmed upload.".to_string()));
     }
 
-    let image_id = result.unwrap();
+    let upload_key = get_upload_key(key);
+    let object = self.store_service.head(upload_key.clone()).await?;
 
-    let result = self.get_one(id).await;
-    if result.is_err() {
-      return Err(result.unwrap_err());
+    // From here on the object is the caller's upload, so it is removed when it is rejected.
+    if !self.upload_repo.mark_used(id, key).await? {
+      return Err(Error::Conflict("The upload was confirmed concurrently.".to_string()));
     }
 
-    let mut post = result.unwrap();
+    if object.content_length > max_size as u64 {
+      self.store_service.delete(object.key).await?;
+      return Err(Error::PayloadTooLarge(format!("File must not exceed {} bytes.", max_size)));
+    }
 
-    if post.image_id != Uuid::nil() {
-      let result = self.store_service.delete(post.image_id).await;
-      if result.is_err() {
-        return Err(result.unwrap_err());
+    // The declared content type comes from the client, so the type is sniffed from the content instead.
+    let (content, _) = self.store_service.get(object.key.clone()).await?;
+    let (content, content_type) = match prepare_image(content) {
+      Ok(image) => image,
+      Err(error) => {
+        self.store_service.delete(object.key).await?;
+        return Err(error);
       }
+    };
+
+    // The upload itself is never served, only the checked image without its metadata is written to the
+    // image key. The upload key becomes the image id.
+    let variants = self.save_image(key, content, content_type).await?;
+
+    self.attach_image(id, key, variants, vec![upload_key]).await
+  }
+
+  #[tracing::instrument(name = "PostService::delete", level = "debug", skip_all, fields(id = %id))]
+  async fn delete(&self, claims: &TokenClaims, id: Uuid, if_match: Option<String>) -> Result<(), Error> {
+    let post = self.get_one(id).await?;
+    check_owner(claims, post.user_id)?;
+    check_if_match(&post, if_match.as_deref())?;
+
+    let mut deleted_keys = Self::get_post_image_keys(&post);
+    deleted_keys.extend(self.get_transform_keys(post.image_id).await?);
+    for media in &post.media {
+      deleted_keys.extend(get_image_keys(media.object_id, &media.widths));
+      deleted_keys.extend(self.get_transform_keys(media.object_id).await?);
     }
 
-    post.image_id = image_id;
+    self.repo.delete(id, post.updated_at, deleted_keys).await?;
+    self.deletion_service.notify();
+
+    Ok(())
+  }
+
+  #[tracing::instrument(name = "PostService::get_media", level = "debug", skip_all, fields(id = %id))]
+  async fn get_media(&self, id: Uuid) -> Result<Vec<PostMedia>, Error> {
+    Ok(self.get_one(id).await?.media)
+  }
+
+  #[tracing::instrument(name = "PostService::add_media", level = "debug", skip_all, fields(id = %id))]
+  async fn add_media(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    content: Bytes,
+    if_match: Option<String>,
+  ) -> Result<PostMedia, Error> {
+    // Media are part of the post representation, so changing them is conditional on its tag.
+    let post = self.get_one(id).await?;
+    check_owner(claims, post.user_id)?;
+    check_if_match(&post, if_match.as_deref())?;
+
+    let (content, content_type) = prepare_image(content)?;
+
+    let object_id = Uuid::new_v4();
+    let variants = self.save_image(object_id, content, content_type.clone()).await?;
+
+    self.media_repo.create(PostMedia {
+      id: Uuid::nil(),
+      post_id: id,
+      object_id,
+      content_type,
+      width: variants.width as i32,
+      widths: variants.widths.iter().map(|width| *width as i32).collect(),
+      position: 0,
+      alt_text: String::new(),
+      caption: String::new(),
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    }).await
+  }
+
+  #[tracing::instrument(name = "PostService::update_media", level = "debug", skip_all, fields(id = %media.id))]
+  async fn update_media(
+    &self,
+    claims: &TokenClaims,
+    media: PostMedia,
+    if_match: Option<String>,
+  ) -> Result<PostMedia, Error> {
+    let post = self.get_one(media.post_id).await?;
+    check_owner(claims, post.user_id)?;
+    check_if_match(&post, if_match.as_deref())?;
+
+    let mut old_media = self.media_repo.get_one(media.post_id, media.id).await?;
+    old_media.alt_text = media.alt_text;
+    old_media.caption = media.caption;
 
-    self.repo.update(post).await
+    self.media_repo.update(old_media).await
   }
 
-  async fn delete(&self, id: Uuid) -> Result<(), Error> {
-    let result = self.get_one(id).await;
-    if result.is_err() {
-      return Err(result.unwrap_err());
+  #[tracing::instrument(name = "PostService::reorder_media", level = "debug", skip_all, fields(id = %id))]
+  async fn reorder_media(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    media_ids: Vec<Uuid>,
+    if_match: Option<String>,
+  ) -> Result<Vec<PostMedia>, Error> {
+    let post = self.get_one(id).await?;
+    check_owner(claims, post.user_id)?;
+    check_if_match(&post, if_match.as_deref())?;
+
+    let media = post.media;
+    let is_permutation = media_ids.len() == media.len()
+      && media.iter().all(|media| media_ids.contains(&media.id));
+    if !is_permutation {
+      return Err(Error::BadRequest("Order must list every media of the post exactly once.".to_string()));
+    }
+
+    self.media_repo.update_positions(id, &media_ids).await?;
+
+    self.get_media(id).await
+  }
+
+  #[tracing::instrument(
+    name = "PostService::delete_media", level = "debug", skip_all, fields(id = %id, media_id = %media_id),
+  )]
+  async fn delete_media(
+    &self,
+    claims: &TokenClaims,
+    id: Uuid,
+    media_id: Uuid,
+    if_match: Option<String>,
+  ) -> Result<(), Error> {
+    let post = self.get_one(id).await?;
+    check_owner(claims, post.user_id)?;
+    check_if_match(&post, if_match.as_deref())?;
+    let media = self.media_repo.get_one(id, media_id).await?;
+
+    if get_media_references(&post.body).contains(&media.id) {
+      return Err(Error::BadRequest("Media is referenced in the post body.".to_string()));
     }
 
-    let post = result.unwrap();
+    let mut deleted_keys = get_image_keys(media.object_id, &media.widths);
+    deleted_keys.extend(self.get_transform_keys(media.object_id).await?);
+
+    self.media_repo.delete(media.id, deleted_keys).await?;
+    self.deletion_service.notify();
+
+    Ok(())
+  }
+
+  #[tracing::instrument(name = "PostService::reindex_images", level = "debug", skip_all, fields(all = %all))]
+  async fn reindex_images(&self, all: bool) -> Result<Vec<Uuid>, Error> {
+    let mut reindexed = Vec::new();
+
+    for post in self.repo.get_all().await? {
+      if post.image_id == Uuid::nil() || (!all && post.image_width > 0) {
+        continue;
+      }
 
-    if post.image_id != Uuid::nil() {
-      let result = self.store_service.delete(post.image_id).await;
-      if result.is_err() {
-        return Err(result.unwrap_err());
+      let id = post.id;
+      match self.reindex_image(post).await {
+        Ok(()) => reindexed.push(id),
+        Err(error) => tracing::warn!(%id, %error, "Cannot reindex post image"),
       }
     }
+    self.deletion_service.notify();
 
-    self.repo.delete(id).await
+    Ok(reindexed)
   }
 }
\ No newline at end of file
@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::enums::error::Error;
+use crate::models::storage_gc_report::StorageGcReport;
+use crate::repository::storage_repository::StorageRepository;
+use crate::services::store_service::StoreService;
+use crate::utils::store_key_util::get_object_id;
+
+#[async_trait]
+pub trait StorageGcService: Send + Sync {
+  /// Deletes stored objects that nothing references anymore and that are
+  /// older than the grace period. With `dry_run` nothing is deleted.
+  async fn collect(&self, dry_run: bool) -> Result<StorageGcReport, Error>;
+}
+
+#[derive(Clone)]
+pub struct StorageGcServiceImpl {
+  repo: Arc<dyn StorageRepository>,
+  store_service: Arc<dyn StoreService>,
+  grace_period: chrono::Duration,
+}
+
+impl StorageGcServiceImpl {
+  pub fn new(
+    repo: Arc<dyn StorageRepository>,
+    store_service: Arc<dyn StoreService>,
+    grace_period: chrono::Duration,
+  ) -> Self {
+    Self { repo, store_service, grace_period }
+  }
+}
+
+#[async_trait]
+impl StorageGcService for StorageGcServiceImpl {
+  #[tracing::instrument(name = "StorageGcService::collect", level = "debug", skip_all, fields(dry_run = %dry_run))]
+  async fn collect(&self, dry_run: bool) -> Result<StorageGcReport, Error> {
+    // Listing before reading the references means an object saved in between is
+    // either missing from the listing or younger than the grace period.
+    let entries = self.store_service.list(String::new()).await?;
+    let referenced: HashSet<Uuid> = self.repo.get_referenced_object_ids().await?.into_iter().collect();
+    let cutoff = Utc::now() - self.grace_period;
+
+    let mut report = StorageGcReport { dry_run, scanned: entries.len(), ..Default::default() };
+
+    for entry in entries {
+      // Keys that do not follow the naming scheme were not written by us.
+      let Some(object_id) = get_object_id(&entry.key) else { continue };
+
+      if referenced.contains(&object_id) || entry.last_modified > cutoff {
+        continue;
+      }
+
+      if !dry_run {
+        self.store_service.delete(entry.key.clone()).await?;
+      }
+
+      report.reclaimed_bytes += entry.size;
+      report.deleted_keys.push(entry.key);
+    }
+
+    Ok(report)
+  }
+}
+
+/// Collects every `interval` until `shutdown` is cancelled, a running collection is finished first.
+pub fn spawn_storage_gc(
+  service: Arc<dyn StorageGcService>,
+  interval: Duration,
+  dry_run: bool,
+  shutdown: CancellationToken,
+) -> JoinHandle<()> {
+  tokio::spawn(async move {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+      tokio::select! {
+        _ = shutdown.cancelled() => break,
+        _ = ticker.tick() => {}
+      }
+
+      match service.collect(dry_run).await {
+        Ok(report) => tracing::info!(
+          dry_run = report.dry_run,
+          scanned = report.scanned,
+          removed = report.deleted_keys.len(),
+          reclaimed_bytes = report.reclaimed_bytes,
+          "Storage GC finished",
+        ),
+        Err(error) => tracing::error!(error = %error, "Storage GC failed"),
+      }
+    }
+  })
+}
@@ -1,54 +1,244 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use axum::body::Bytes;
+use axum::body::{Body, Bytes};
+use chrono::{DateTime, Utc};
+use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE, HeaderMap, HeaderValue, RANGE};
+use reqwest::StatusCode;
 use s3::Bucket;
+use tracing::Instrument;
+use tracing::field::Empty;
 use uuid::Uuid;
 
+use crate::config::metrics::Metrics;
 use crate::enums::error::Error;
+use crate::models::store_entry::StoreEntry;
+use crate::models::store_object::StoreObject;
+use crate::utils::trace_util::propagation_headers;
+
+// Lifetime of the presigned URLs used to stream objects out of the bucket.
+const STREAM_URL_TTL_SECS: u32 = 60;
 
 #[async_trait]
 pub trait StoreService: Send + Sync {
+  async fn head(&self, key: String) -> Result<StoreObject, Error>;
+  async fn get(&self, key: String) -> Result<(Bytes, String), Error>;
+  /// Streams an object, or the inclusive byte range of it when given.
+  async fn stream(&self, key: String, range: Option<(u64, u64)>) -> Result<Body, Error>;
   async fn save(&self, content: Bytes, content_type: String) -> Result<Uuid, Error>;
-  async fn delete(&self, id: Uuid) -> Result<(), Error>;
+  async fn save_with_key(&self, key: String, content: Bytes, content_type: String) -> Result<(), Error>;
+  async fn delete(&self, key: String) -> Result<(), Error>;
+  /// Returns a URL that lets a client PUT the object straight into the store. The content type and
+  /// length are part of the signature, so the upload must send exactly these.
+  async fn presign_put(
+    &self,
+    key: String,
+    content_type: String,
+    content_length: u64,
+    expiry_secs: u32,
+  ) -> Result<String, Error>;
+  /// Lists the objects whose key starts with `prefix`, every object for an empty one.
+  async fn list(&self, prefix: String) -> Result<Vec<StoreEntry>, Error>;
+  /// Checks that the store can be reached, for readiness probes.
+  async fn ping(&self) -> Result<(), Error>;
 }
 
 #[derive(Clone)]
 pub struct StoreServiceImpl {
   store: Arc<Bucket>,
+  client: reqwest::Client,
+  metrics: Arc<Metrics>,
 }
 
 impl StoreServiceImpl {
-  pub fn new(store: Arc<Bucket>) -> Self {
+  pub fn new(store: Arc<Bucket>, metrics: Arc<Metrics>) -> Self {
     StoreServiceImpl {
       store,
+      client: reqwest::Client::new(),
+      metrics,
     }
   }
+
+  fn record<T>(&self, operation: &str, result: Result<T, Error>) -> Result<T, Error> {
+    let outcome = match &result {
+      Ok(_) => "success",
+      Err(Error::NotFound(_)) => "not_found",
+      Err(_) => "error",
+    };
+    self.metrics.store_operations_total.with_label_values(&[operation, outcome]).inc();
+
+    result
+  }
 }
 
 #[async_trait]
 impl StoreService for StoreServiceImpl {
+  #[tracing::instrument(name = "StoreService::head", level = "debug", skip_all, fields(key = %key))]
+  async fn head(&self, key: String) -> Result<StoreObject, Error> {
+    let result = async {
+      let (head, _) = self.store
+        .head_object(key.as_str())
+        .await
+        .map_err(Error::from)?;
+
+      Ok(StoreObject {
+        key,
+        content_type: head.content_type.unwrap_or_else(|| "application/octet-stream".to_string()),
+        content_length: head.content_length.unwrap_or_default().max(0) as u64,
+        e_tag: head.e_tag,
+        last_modified: head.last_modified,
+      })
+    }.await;
+
+    self.record("head", result)
+  }
+
+  #[tracing::instrument(name = "StoreService::get", level = "debug", skip_all, fields(key = %key))]
+  async fn get(&self, key: String) -> Result<(Bytes, String), Error> {
+    let result = async {
+      let response = self.store
+        .get_object(key)
+        .await
+        .map_err(Error::from)?;
+
+      let content_type = response.headers()
+        .get("content-type")
+        .cloned()
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+      Ok((response.bytes().clone(), content_type))
+    }.await;
+
+    self.record("get", result)
+  }
+
+  #[tracing::instrument(name = "StoreService::stream", level = "debug", skip_all, fields(key = %key))]
+  async fn stream(&self, key: String, range: Option<(u64, u64)>) -> Result<Body, Error> {
+    let result = async {
+      let url = self.store
+        .presign_get(key, STREAM_URL_TTL_SECS, None)
+        .map_err(|error| Error::internal("Error reading file.", error))?;
+
+      // Client span of the outbound call, the trace is passed on to the store.
+      let span = tracing::debug_span!("StoreService::stream GET", otel.kind = "client", http.response.status_code = Empty);
+      let mut request = self.client.get(url);
+      if let Some((start, end)) = range {
+        request = request.header(RANGE, format!("bytes={}-{}", start, end));
+      }
+      for (name, value) in propagation_headers(&span) {
+        request = request.header(name, value);
+      }
+
+      let response = request
+        .send()
+        .instrument(span.clone())
+        .await
+        .map_err(|error| Error::internal("Error reading file.", error))?;
+      span.record("http.response.status_code", response.status().as_u16());
+
+      match response.status() {
+        status if status.is_success() => Ok(Body::from_stream(response.bytes_stream())),
+        StatusCode::NOT_FOUND => Err(Error::NotFound("File not found.".to_string())),
+        status => Err(Error::internal("Error reading file.", format!("Storage responded with {}", status))),
+      }
+    }.await;
+
+    self.record("stream", result)
+  }
+
+  #[tracing::instrument(name = "StoreService::save", level = "debug", skip_all)]
   async fn save(&self, content: Bytes, content_type: String) -> Result<Uuid, Error> {
     let id = Uuid::new_v4();
 
-    let result = self.store
-      .put_object_with_content_type(id.to_string(),
-                                    &content.to_vec(),
-                                    content_type.as_str()).await;
-    if result.is_err() {
-      return Err(Error::InternalServerError("Error saving file.".to_string()));
-    }
+    self.save_with_key(id.to_string(), content, content_type).await?;
 
     Ok(id)
   }
 
-  async fn delete(&self, id: Uuid) -> Result<(), Error> {
-    let result = self.store.delete_object(id.to_string()).await;
+  #[tracing::instrument(name = "StoreService::save_with_key", level = "debug", skip_all, fields(key = %key))]
+  async fn save_with_key(&self, key: String, content: Bytes, content_type: String) -> Result<(), Error> {
+    let result = async {
+      self.store
+        .put_object_with_content_type(key,
+                                      &content,
+                                      content_type.as_str()).await?;
 
-    if result.is_err() {
-      return Err(Error::InternalServerError("Error deleting file.".to_string()));
-    }
+      Ok(())
+    }.await;
+
+    self.record("save", result)
+  }
+
+  #[tracing::instrument(name = "StoreService::delete", level = "debug", skip_all, fields(key = %key))]
+  async fn delete(&self, key: String) -> Result<(), Error> {
+    let result = async {
+      self.store.delete_object(key).await?;
+
+      Ok(())
+    }.await;
+
+    self.record("delete", result)
+  }
+
+  #[tracing::instrument(name = "StoreService::presign_put", level = "debug", skip_all, fields(key = %key))]
+  async fn presign_put(
+    &self,
+    key: String,
+    content_type: String,
+    content_length: u64,
+    expiry_secs: u32,
+  ) -> Result<String, Error> {
+    let result = async {
+      let mut headers = HeaderMap::new();
+      headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_str(content_type.as_str()).map_err(|_| Error::BadRequest("Invalid content type.".to_string()))?,
+      );
+      headers.insert(CONTENT_LENGTH, HeaderValue::from(content_length));
+
+      self.store
+        .presign_put(key, expiry_secs, Some(headers))
+        .map_err(|error| Error::internal("Error creating upload URL.", error))
+    }.await;
+
+    self.record("presign_put", result)
+  }
+
+  #[tracing::instrument(name = "StoreService::list", level = "debug", skip_all, fields(prefix = %prefix))]
+  async fn list(&self, prefix: String) -> Result<Vec<StoreEntry>, Error> {
+    let result = async {
+      let pages = self.store
+        .list(prefix, None)
+        .await?;
+
+      let mut entries: Vec<StoreEntry> = Vec::new();
+      for object in pages.into_iter().flat_map(|page| page.contents) {
+        let last_modified = DateTime::parse_from_rfc3339(object.last_modified.as_str())
+          .map_err(|error| Error::internal("Error listing files.", error))?;
+
+        entries.push(StoreEntry {
+          key: object.key,
+          size: object.size,
+          last_modified: last_modified.with_timezone(&Utc),
+        });
+      }
+
+      Ok(entries)
+    }.await;
+
+    self.record("list", result)
+  }
+
+  #[tracing::instrument(name = "StoreService::ping", level = "debug", skip_all)]
+  async fn ping(&self) -> Result<(), Error> {
+    let result = async {
+      self.store
+        .list_page(String::new(), None, None, None, Some(1))
+        .await?;
+
+      Ok(())
+    }.await;
 
-    Ok(())
+    self.record("ping", result)
   }
 }
\ No newline at end of file
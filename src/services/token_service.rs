@@ -12,14 +12,17 @@ use crate::repository::token_repository::TokenRepository;
 
 #[async_trait]
 pub trait TokenService: Send + Sync {
-    async fn get_access_token(&self, user_id: Uuid, token_id: Uuid) -> Result<String, Error>;
-    async fn get_refresh_token(&self, user_id: Uuid, token_id: Uuid) -> Result<String, Error>;
+    async fn get_access_token(&self, user_id: Uuid, token_id: Uuid) -> Result<Option<String>, Error>;
+    /// Removes the refresh token and returns it, a refresh token can only be used once.
+    async fn take_refresh_token(&self, user_id: Uuid, token_id: Uuid) -> Result<Option<String>, Error>;
     async fn save_access_token(&self, user_id: Uuid, token_id: Uuid, token: String) -> Result<bool, Error>;
     async fn save_refresh_token(&self, user_id: Uuid, token_id: Uuid, token: String) -> Result<bool, Error>;
     async fn delete_access_tokens(&self, user_id: Uuid, token_id: Uuid) -> Result<bool, Error>;
     async fn delete_refresh_tokens(&self, user_id: Uuid, token_id: Uuid) -> Result<bool, Error>;
-    fn generate_access_token(&self, user_id: Uuid, token_id: Uuid) -> Result<String, Error>;
-    fn generate_refresh_token(&self, user_id: Uuid, token_id: Uuid) -> Result<String, Error>;
+    /// Deletes the stored tokens of every session of a user, returning how many were deleted.
+    async fn delete_user_tokens(&self, user_id: Uuid) -> Result<usize, Error>;
+    fn generate_access_token(&self, user_id: Uuid, token_id: Uuid, role: &str) -> Result<String, Error>;
+    fn generate_refresh_token(&self, user_id: Uuid, token_id: Uuid, role: &str) -> Result<String, Error>;
 }
 
 #[derive(Clone)]
@@ -36,59 +39,79 @@ impl TokenServiceImpl {
 
 #[async_trait]
 impl TokenService for TokenServiceImpl {
-    async fn get_access_token(&self, user_id: Uuid, token_id: Uuid) -> Result<String, Error> {
+    #[tracing::instrument(
+        name = "TokenService::get_access_token", level = "debug", skip_all, fields(user_id = %user_id, token_id = %token_id),
+    )]
+    async fn get_access_token(&self, user_id: Uuid, token_id: Uuid) -> Result<Option<String>, Error> {
         let key = format!("{}.{}.at", user_id, token_id);
-        match self.repository.get_token(key).await {
-            Ok(token) => Ok(token.clone()),
-            Err(_) => Err(Error::InternalServerError("Cannot get access token.".to_string())),
-        }
+        self.repository.get_token(key).await
     }
 
-    async fn get_refresh_token(&self, user_id: Uuid, token_id: Uuid) -> Result<String, Error> {
+    #[tracing::instrument(
+        name = "TokenService::take_refresh_token", level = "debug", skip_all, fields(user_id = %user_id, token_id = %token_id),
+    )]
+    async fn take_refresh_token(&self, user_id: Uuid, token_id: Uuid) -> Result<Option<String>, Error> {
         let key = format!("{}.{}.rt", user_id, token_id);
-        match self.repository.get_token(key).await {
-            Ok(token) => Ok(token.clone()),
-            Err(_) => Err(Error::InternalServerError("Cannot get refresh token".to_string())),
-        }
+        self.repository.take_token(key).await
     }
 
+    #[tracing::instrument(
+        name = "TokenService::save_access_token", level = "debug", skip_all, fields(user_id = %user_id, token_id = %token_id),
+    )]
     async fn save_access_token(&self, user_id: Uuid, token_id: Uuid, token: String) -> Result<bool, Error> {
         let key = format!("{}.{}.at", user_id, token_id);
-        let exp = Duration::hours(1);
+        let exp = Duration::seconds(self.settings.access_token_ttl_secs);
         match self.repository.save_token(key, token, exp).await {
             Ok(_) => Ok(true),
-            Err(_) => Err(Error::InternalServerError("Cannot save access token.".to_string()))
+            Err(error) => Err(Error::internal("Cannot save access token.", error))
         }
     }
 
+    #[tracing::instrument(
+        name = "TokenService::save_refresh_token", level = "debug", skip_all, fields(user_id = %user_id, token_id = %token_id),
+    )]
     async fn save_refresh_token(&self, user_id: Uuid, token_id: Uuid, token: String) -> Result<bool, Error> {
         let key = format!("{}.{}.rt", user_id, token_id);
-        let exp = Duration::hours(24 * 7);
+        let exp = Duration::seconds(self.settings.refresh_token_ttl_secs);
         match self.repository.save_token(key, token, exp).await {
             Ok(_) => Ok(true),
-            Err(_) => Err(Error::InternalServerError("Cannot save refresh token.".to_string()))
+            Err(error) => Err(Error::internal("Cannot save refresh token.", error))
         }
     }
 
+    #[tracing::instrument(
+        name = "TokenService::delete_access_tokens", level = "debug", skip_all, fields(user_id = %user_id, token_id = %token_id),
+    )]
     async fn delete_access_tokens(&self, user_id: Uuid, token_id: Uuid) -> Result<bool, Error> {
         let key = format!("{}.{}.at", user_id, token_id);
         match self.repository.delete_token(key).await {
             Ok(_) => Ok(true),
-            Err(_) => Err(Error::InternalServerError("Cannot delete access token.".to_string())),
+            Err(error) => Err(Error::internal("Cannot delete access token.", error)),
         }
     }
 
+    #[tracing::instrument(
+        name = "TokenService::delete_refresh_tokens", level = "debug", skip_all, fields(user_id = %user_id, token_id = %token_id),
+    )]
     async fn delete_refresh_tokens(&self, user_id: Uuid, token_id: Uuid) -> Result<bool, Error> {
         let key = format!("{}.{}.rt", user_id, token_id);
         match self.repository.delete_token(key).await {
             Ok(_) => Ok(true),
-            Err(_) => Err(Error::InternalServerError("Cannot delete refresh token.".to_string())),
+            Err(error) => Err(Error::internal("Cannot delete refresh token.", error)),
+        }
+    }
+
+    #[tracing::instrument(name = "TokenService::delete_user_tokens", level = "debug", skip_all, fields(user_id = %user_id))]
+    async fn delete_user_tokens(&self, user_id: Uuid) -> Result<usize, Error> {
+        match self.repository.delete_tokens_with_prefix(format!("{}.", user_id)).await {
+            Ok(count) => Ok(count),
+            Err(error) => Err(Error::internal("Cannot delete user tokens.", error)),
         }
     }
 
-    fn generate_access_token(&self, user_id: Uuid, token_id: Uuid) -> Result<String, Error> {
+    fn generate_access_token(&self, user_id: Uuid, token_id: Uuid, role: &str) -> Result<String, Error> {
         let current_time = Utc::now();
-        let expiration = current_time + Duration::hours(1); // 1 hour expiration
+        let expiration = current_time + Duration::seconds(self.settings.access_token_ttl_secs);
         let claims = TokenClaims {
             iss: String::from("rustblog"),
             sub: user_id,
@@ -96,18 +119,19 @@ impl TokenService for TokenServiceImpl {
             iat: current_time.timestamp(),
             exp: expiration.timestamp(),
             is_refresh: false,
+            role: role.to_string(),
         };
 
         let secret = self.settings.access_secret.as_bytes();
         match encode(&Header::new(Algorithm::HS256), &claims, &EncodingKey::from_secret(secret)) {
             Ok(token) => Ok(token),
-            Err(_) => Err(Error::InternalServerError("Cannot generate access token.".to_string())),
+            Err(error) => Err(Error::internal("Cannot generate access token.", error)),
         }
     }
 
-    fn generate_refresh_token(&self, user_id: Uuid, token_id: Uuid) -> Result<String, Error> {
+    fn generate_refresh_token(&self, user_id: Uuid, token_id: Uuid, role: &str) -> Result<String, Error> {
         let current_time = Utc::now();
-        let expiration = current_time + Duration::days(7); // 7 days expiration
+        let expiration = current_time + Duration::seconds(self.settings.refresh_token_ttl_secs);
         let claims = TokenClaims {
             iss: String::from("rustblog"),
             sub: user_id,
@@ -115,12 +139,13 @@ impl TokenService for TokenServiceImpl {
             iat: current_time.timestamp(),
             exp: expiration.timestamp(),
             is_refresh: true,
+            role: role.to_string(),
         };
 
         let secret = self.settings.refresh_secret.as_bytes();
         match encode(&Header::new(Algorithm::HS256), &claims, &EncodingKey::from_secret(secret)) {
             Ok(token) => Ok(token),
-            Err(_) => Err(Error::InternalServerError("Cannot generate refresh token.".to_string())),
+            Err(error) => Err(Error::internal("Cannot generate refresh token.", error)),
         }
     }
 }
\ No newline at end of file
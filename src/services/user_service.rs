@@ -13,6 +13,7 @@ pub trait UserService: Send + Sync {
     async fn get_one(&self, id: Uuid) -> Result<Option<User>, Error>;
     async fn get_by_email(&self, email: String) -> Result<Option<User>, Error>;
     async fn create(&self, user: CreateUser) -> Result<User, Error>;
+    async fn update(&self, user: User) -> Result<User, Error>;
     async fn delete(&self, id: Uuid) -> Result<(), Error>;
 }
 
@@ -29,22 +30,32 @@ impl UserServiceImpl {
 
 #[async_trait]
 impl UserService for UserServiceImpl {
+    #[tracing::instrument(name = "UserService::get_all", level = "debug", skip_all)]
     async fn get_all(&self) -> Result<Vec<User>, Error> {
         self.repository.all().await
     }
 
+    #[tracing::instrument(name = "UserService::get_one", level = "debug", skip_all, fields(id = %id))]
     async fn get_one(&self, id: Uuid) -> Result<Option<User>, Error> {
         self.repository.get_by_id(id).await
     }
 
+    #[tracing::instrument(name = "UserService::get_by_email", level = "debug", skip_all)]
     async fn get_by_email(&self, email: String) -> Result<Option<User>, Error> {
         self.repository.get_by_email(email).await
     }
 
+    #[tracing::instrument(name = "UserService::create", level = "debug", skip_all)]
     async fn create(&self, user: CreateUser) -> Result<User, Error> {
         self.repository.create(user.clone()).await
     }
 
+    #[tracing::instrument(name = "UserService::update", level = "debug", skip_all, fields(id = %user.id))]
+    async fn update(&self, user: User) -> Result<User, Error> {
+        self.repository.update(user.id, user).await
+    }
+
+    #[tracing::instrument(name = "UserService::delete", level = "debug", skip_all, fields(id = %id))]
     async fn delete(&self, id: Uuid) -> Result<(), Error> {
         self.repository.delete(id).await
     }
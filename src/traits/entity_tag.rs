@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::enums::error::Error;
+use crate::models::comment::Comment;
+use crate::models::post::Post;
+use crate::utils::http_util::etag_matches_strong;
+
+/// Strong entity tag of the representation returned to clients. It must change
+/// whenever anything in that representation does.
+pub trait EntityTag {
+  fn entity_tag(&self) -> String;
+}
+
+/// Tags are a truncated SHA-256 of a fixed byte encoding so they stay valid
+/// across builds and deployments.
+fn to_entity_tag(bytes: &[u8]) -> String {
+  let digest = Sha256::digest(bytes);
+  let hex: String = digest[..8].iter().map(|byte| format!("{:02x}", byte)).collect();
+
+  format!("\"{}\"", hex)
+}
+
+fn push_version(bytes: &mut Vec<u8>, id: Uuid, updated_at: DateTime<Utc>) {
+  bytes.extend_from_slice(id.as_bytes());
+  bytes.extend_from_slice(&updated_at.timestamp_micros().to_be_bytes());
+}
+
+impl EntityTag for Post {
+  fn entity_tag(&self) -> String {
+    let mut bytes = Vec::new();
+    push_version(&mut bytes, self.id, self.updated_at);
+
+    for media in &self.media {
+      push_version(&mut bytes, media.id, media.updated_at);
+      bytes.extend_from_slice(&media.position.to_be_bytes());
+    }
+
+    to_entity_tag(&bytes)
+  }
+}
+
+impl EntityTag for Comment {
+  fn entity_tag(&self) -> String {
+    let mut bytes = Vec::new();
+    push_version(&mut bytes, self.id, self.updated_at);
+
+    to_entity_tag(&bytes)
+  }
+}
+
+impl<T: EntityTag> EntityTag for Vec<T> {
+  fn entity_tag(&self) -> String {
+    let tags: String = self.iter().map(EntityTag::entity_tag).collect();
+
+    to_entity_tag(tags.as_bytes())
+  }
+}
+
+/// Fails with 412 when an `If-Match` header was sent and does not match the entity.
+pub fn check_if_match<T: EntityTag>(entity: &T, if_match: Option<&str>) -> Result<(), Error> {
+  match if_match {
+    Some(if_match) if !etag_matches_strong(if_match, &entity.entity_tag()) => {
+      Err(Error::PreconditionFailed("The resource has been modified since it was fetched.".to_string()))
+    }
+    _ => Ok(()),
+  }
+}
\ No newline at end of file
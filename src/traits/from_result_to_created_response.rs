@@ -0,0 +1,28 @@
+use axum::http::header::LOCATION;
+use axum::http::{HeaderName, StatusCode};
+use axum::Json;
+
+use crate::enums::error::Error;
+
+pub type CreatedResponse<U> = (StatusCode, [(HeaderName, String); 1], Json<U>);
+
+pub trait FromResultToCreatedResponse<T> {
+  /// Responds with `201 Created` and a `Location` header pointing at the new resource.
+  fn to_created_response<U, F, L>(self, location: L, op: F) -> Result<CreatedResponse<U>, Error>
+    where
+      L: FnOnce(&T) -> String,
+      F: FnOnce(T) -> U;
+}
+
+impl<T> FromResultToCreatedResponse<T> for Result<T, Error> {
+  fn to_created_response<U, F, L>(self, location: L, op: F) -> Result<CreatedResponse<U>, Error>
+    where
+      L: FnOnce(&T) -> String,
+      F: FnOnce(T) -> U,
+  {
+    match self {
+      Ok(value) => Ok((StatusCode::CREATED, [(LOCATION, location(&value))], Json(op(value)))),
+      Err(error) => Err(error),
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+use axum::http::header::ETAG;
+use axum::http::StatusCode;
+use axum::Json;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+use crate::enums::error::Error;
+use crate::traits::entity_tag::EntityTag;
+use crate::utils::http_util::etag_matches;
+
+pub trait FromResultToTaggedResponse<T> {
+  /// Responds with the entity and its `ETag`, or with `304 Not Modified` when
+  /// `if_none_match` already matches it.
+  fn to_tagged_response<U, F>(self, if_none_match: Option<String>, op: F) -> Result<Response, Error>
+    where
+      U: Serialize,
+      F: FnOnce(T) -> U;
+}
+
+impl<T: EntityTag> FromResultToTaggedResponse<T> for Result<T, Error> {
+  fn to_tagged_response<U, F>(self, if_none_match: Option<String>, op: F) -> Result<Response, Error>
+    where
+      U: Serialize,
+      F: FnOnce(T) -> U,
+  {
+    let value = self?;
+    let e_tag = value.entity_tag();
+
+    if if_none_match.is_some_and(|if_none_match| etag_matches(&if_none_match, &e_tag)) {
+      return Ok((StatusCode::NOT_MODIFIED, [(ETAG, e_tag)]).into_response());
+    }
+
+    Ok((StatusCode::OK, [(ETAG, e_tag)], Json(op(value))).into_response())
+  }
+}
\ No newline at end of file
@@ -1,2 +1,6 @@
+pub mod entity_tag;
+pub mod from_result_to_created_response;
 pub mod from_result_to_response;
-pub mod from_result_to_response_with_no_content;
\ No newline at end of file
+pub mod from_result_to_response_with_no_content;
+pub mod from_result_to_tagged_response;
+pub mod validate;
@@ -0,0 +1,7 @@
+use crate::enums::error::Error;
+
+/// Checks a request DTO before it reaches a service. Implementations should
+/// report every invalid field at once through `Error::Validation`.
+pub trait Validate {
+  fn validate(&self) -> Result<(), Error>;
+}
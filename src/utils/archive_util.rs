@@ -0,0 +1,22 @@
+use std::io::{Cursor, Write};
+
+use serde::Serialize;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use crate::enums::error::Error;
+
+pub fn add_file(archive: &mut ZipWriter<Cursor<Vec<u8>>>, name: String, content: &[u8]) -> Result<(), Error> {
+  archive.start_file(name, FileOptions::default())
+    .map_err(|error| Error::internal("Error while creating archive.", error))?;
+
+  archive.write_all(content)
+    .map_err(|error| Error::internal("Error while creating archive.", error))
+}
+
+pub fn add_json<T: Serialize>(archive: &mut ZipWriter<Cursor<Vec<u8>>>, name: String, value: &T) -> Result<(), Error> {
+  let content = serde_json::to_vec_pretty(value)
+    .map_err(|error| Error::internal("Error while serializing archive entry.", error))?;
+
+  add_file(archive, name, &content)
+}
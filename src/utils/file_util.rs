@@ -1,27 +1,50 @@
 use axum::body::Bytes;
+use axum::extract::multipart::MultipartError;
 use axum::extract::Multipart;
+use axum::http::StatusCode;
 
 use crate::enums::error::Error;
 
-pub async fn get_file_from_multipart(name: String, mut multipart: Multipart) -> Result<(Bytes, String), Error> {
-
-  let result = multipart.next_field().await;
-  if result.is_err() {
-    return Err(Error::InternalServerError("Something went wrong.".to_string()));
+fn map_multipart_error(error: MultipartError) -> Error {
+  if error.status() == StatusCode::PAYLOAD_TOO_LARGE {
+    return Error::PayloadTooLarge("File is too large.".to_string());
   }
 
-  match result.unwrap() {
-    Some(field) => {
-      let field_name = field.name().unwrap();
-      if field_name != name {
-        return Err(Error::BadRequest("File was not provided.".to_string()));
+  Error::BadRequest("Malformed multipart body.".to_string())
+}
+
+pub async fn get_file_from_multipart(name: String, mut multipart: Multipart, max_size: usize) -> Result<Bytes, Error> {
+  while let Some(mut field) = multipart.next_field().await.map_err(map_multipart_error)? {
+    if field.name() != Some(name.as_str()) {
+      continue;
+    }
+
+    let mut content = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(map_multipart_error)? {
+      if content.len() + chunk.len() > max_size {
+        return Err(Error::PayloadTooLarge(format!("File must not exceed {} bytes.", max_size)));
       }
 
-      let content_type = field.content_type().unwrap().to_string();
-      let content = field.bytes().await.unwrap();
+      content.extend_from_slice(&chunk);
+    }
 
-      Ok((content, content_type))
+    if content.is_empty() {
+      return Err(Error::BadRequest("File is empty.".to_string()));
     }
-    None => Err(Error::InternalServerError("Something went wrong.".to_string()))
+
+    return Ok(Bytes::from(content));
+  }
+
+  Err(Error::BadRequest("File was not provided.".to_string()))
+}
+
+pub fn get_extension_for_content_type(content_type: &str) -> &'static str {
+  match content_type {
+    "image/jpeg" => "jpg",
+    "image/png" => "png",
+    "image/webp" => "webp",
+    "image/gif" => "gif",
+    "application/zip" => "zip",
+    _ => "bin",
   }
-}
\ No newline at end of file
+}
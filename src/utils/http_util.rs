@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ByteRange {
+  Full,
+  Partial(u64, u64),
+  Unsatisfiable,
+}
+
+/// Resolves a `Range` header against the object length. Only single
+/// `bytes` ranges are honoured; anything else falls back to the full body.
+pub fn parse_range(header: &str, length: u64) -> ByteRange {
+  let spec = match header.trim().strip_prefix("bytes=") {
+    Some(spec) if !spec.contains(',') => spec.trim(),
+    _ => return ByteRange::Full,
+  };
+
+  let (start, end) = match spec.split_once('-') {
+    Some(bounds) => bounds,
+    None => return ByteRange::Full,
+  };
+
+  let range = match (start.parse::<u64>(), end.parse::<u64>()) {
+    (Ok(start), Ok(end)) if start <= end => (start, end.min(length.saturating_sub(1))),
+    (Ok(start), Err(_)) if end.is_empty() => (start, length.saturating_sub(1)),
+    (Err(_), Ok(suffix)) if start.is_empty() && suffix > 0 => (length.saturating_sub(suffix), length.saturating_sub(1)),
+    _ => return ByteRange::Full,
+  };
+
+  if length == 0 || range.0 >= length {
+    return ByteRange::Unsatisfiable;
+  }
+
+  ByteRange::Partial(range.0, range.1)
+}
+
+/// Weak comparison of an `If-None-Match` header against an entity tag.
+pub fn etag_matches(if_none_match: &str, e_tag: &str) -> bool {
+  let normalize = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+  let e_tag = normalize(e_tag);
+
+  if_none_match
+    .split(',')
+    .any(|candidate| candidate.trim() == "*" || normalize(candidate) == e_tag)
+}
+
+/// Strong comparison of an `If-Match` header against an entity tag.
+pub fn etag_matches_strong(if_match: &str, e_tag: &str) -> bool {
+  if_match
+    .split(',')
+    .map(|candidate| candidate.trim())
+    .any(|candidate| candidate == "*" || (!candidate.starts_with("W/") && candidate == e_tag))
+}
+
+pub fn format_http_date(date: DateTime<Utc>) -> String {
+  date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
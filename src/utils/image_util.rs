@@ -0,0 +1,706 @@
+use std::io::Cursor;
+
+use axum::body::Bytes;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::imageops::FilterType;
+use image::error::ImageError;
+use image::{DynamicImage, ImageFormat, ImageReader, Limits};
+use uuid::Uuid;
+
+use crate::enums::error::Error;
+use crate::enums::image_fit::ImageFit;
+
+const PNG_SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+// PNG chunks that carry EXIF, XMP or free-form text metadata.
+const PNG_METADATA_CHUNKS: &[&[u8]] = &[b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+
+// WebP VP8X flags announcing EXIF and XMP chunks.
+const WEBP_EXIF_FLAG: u8 = 0x08;
+const WEBP_XMP_FLAG: u8 = 0x04;
+
+fn malformed() -> Error {
+  Error::BadRequest("Malformed image.".to_string())
+}
+
+fn get_bytes(content: &[u8], start: usize, length: usize) -> Result<&[u8], Error> {
+  start.checked_add(length)
+    .and_then(|end| content.get(start..end))
+    .ok_or_else(malformed)
+}
+
+fn get_byte(content: &[u8], position: usize) -> Result<u8, Error> {
+  content.get(position).copied().ok_or_else(malformed)
+}
+
+fn read_u16_be(content: &[u8], position: usize) -> Result<usize, Error> {
+  let bytes = get_bytes(content, position, 2)?;
+
+  Ok(u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+}
+
+fn read_u32_be(content: &[u8], position: usize) -> Result<usize, Error> {
+  let bytes = get_bytes(content, position, 4)?;
+
+  Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+}
+
+fn read_u32_le(content: &[u8], position: usize) -> Result<usize, Error> {
+  let bytes = get_bytes(content, position, 4)?;
+
+  Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+}
+
+pub const IMAGE_CONTENT_TYPES: &[&str] = &["image/jpeg", "image/png", "image/webp", "image/gif"];
+
+pub fn sniff_image_content_type(content: &[u8]) -> Option<&'static str> {
+  if content.starts_with(&[0xFF, 0xD8, 0xFF]) {
+    Some("image/jpeg")
+  } else if content.starts_with(PNG_SIGNATURE) {
+    Some("image/png")
+  } else if content.starts_with(b"GIF87a") || content.starts_with(b"GIF89a") {
+    Some("image/gif")
+  } else if content.len() >= 12 && &content[0..4] == b"RIFF" && &content[8..12] == b"WEBP" {
+    Some("image/webp")
+  } else {
+    None
+  }
+}
+
+/// Checks that the content is a supported image and strips EXIF/XMP metadata
+/// (including GPS coordinates) from it. Returns the cleaned content and its
+/// sniffed content type.
+pub fn prepare_image(content: Bytes) -> Result<(Bytes, String), Error> {
+  let content_type = sniff_image_content_type(&content)
+    .ok_or_else(|| Error::BadRequest("Only JPEG, PNG, WebP and GIF images are allowed.".to_string()))?;
+
+  let content = match content_type {
+    "image/jpeg" => strip_jpeg_metadata(&content)?,
+    "image/png" => strip_png_metadata(&content)?,
+    "image/webp" => strip_webp_metadata(&content)?,
+    _ => strip_gif_metadata(&content)?,
+  };
+
+  Ok((Bytes::from(content), content_type.to_string()))
+}
+
+fn strip_jpeg_metadata(content: &[u8]) -> Result<Vec<u8>, Error> {
+  let mut output = Vec::with_capacity(content.len());
+  output.extend_from_slice(get_bytes(content, 0, 2)?);
+
+  let mut position = 2;
+  loop {
+    if get_byte(content, position)? != 0xFF {
+      return Err(malformed());
+    }
+
+    let marker = get_byte(content, position + 1)?;
+
+    // Fill bytes and standalone markers carry no length.
+    if marker == 0xFF {
+      position += 1;
+      continue;
+    }
+    if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+      output.extend_from_slice(get_bytes(content, position, 2)?);
+      position += 2;
+      continue;
+    }
+    if marker == 0xD9 {
+      output.extend_from_slice(get_bytes(content, position, 2)?);
+      return Ok(output);
+    }
+
+    let length = read_u16_be(content, position + 2)?;
+    if length < 2 {
+      return Err(malformed());
+    }
+    let segment = get_bytes(content, position, 2 + length)?;
+
+    // Start of scan: the entropy-coded data runs until the end of the file.
+    if marker == 0xDA {
+      output.extend_from_slice(&content[position..]);
+      return Ok(output);
+    }
+
+    // APP1 holds EXIF and XMP, APP13 holds Photoshop IPTC data.
+    if marker != 0xE1 && marker != 0xED {
+      output.extend_from_slice(segment);
+    }
+
+    position += segment.len();
+  }
+}
+
+fn strip_png_metadata(content: &[u8]) -> Result<Vec<u8>, Error> {
+  let mut output = Vec::with_capacity(content.len());
+  output.extend_from_slice(PNG_SIGNATURE);
+
+  let mut position = PNG_SIGNATURE.len();
+  while position < content.len() {
+    let length = read_u32_be(content, position)?;
+    let chunk_type = get_bytes(content, position + 4, 4)?;
+    let chunk = get_bytes(content, position, length.checked_add(12).ok_or_else(malformed)?)?;
+
+    if !PNG_METADATA_CHUNKS.contains(&chunk_type) {
+      output.extend_from_slice(chunk);
+    }
+
+    position += chunk.len();
+    if chunk_type == b"IEND" {
+      break;
+    }
+  }
+
+  Ok(output)
+}
+
+fn strip_webp_metadata(content: &[u8]) -> Result<Vec<u8>, Error> {
+  let mut chunks = Vec::with_capacity(content.len());
+
+  let mut position = 12;
+  while position < content.len() {
+    let fourcc = get_bytes(content, position, 4)?;
+    let length = read_u32_le(content, position + 4)?;
+    get_bytes(content, position, length.checked_add(8).ok_or_else(malformed)?)?;
+    // Chunks are padded to an even length, the padding of the last one may be missing.
+    let end = (position + 8 + length + (length % 2)).min(content.len());
+
+    match fourcc {
+      b"EXIF" | b"XMP " => {}
+      b"VP8X" => {
+        let start = chunks.len();
+        chunks.extend_from_slice(&content[position..end]);
+        if length > 0 {
+          chunks[start + 8] &= !(WEBP_EXIF_FLAG | WEBP_XMP_FLAG);
+        }
+      }
+      _ => chunks.extend_from_slice(&content[position..end]),
+    }
+
+    position = end;
+  }
+
+  let mut output = Vec::with_capacity(chunks.len() + 12);
+  output.extend_from_slice(b"RIFF");
+  output.extend_from_slice(&((chunks.len() + 4) as u32).to_le_bytes());
+  output.extend_from_slice(b"WEBP");
+  output.extend_from_slice(&chunks);
+
+  Ok(output)
+}
+
+/// Skips the data sub-blocks that follow a GIF extension or image descriptor and
+/// returns the position after their terminator.
+fn skip_gif_sub_blocks(content: &[u8], mut position: usize) -> Result<usize, Error> {
+  loop {
+    let size = get_byte(content, position)? as usize;
+    position += 1;
+    if size == 0 {
+      return Ok(position);
+    }
+    get_bytes(content, position, size)?;
+    position += size;
+  }
+}
+
+fn get_gif_color_table_size(packed: u8) -> usize {
+  if packed & 0x80 == 0 {
+    return 0;
+  }
+
+  3 << ((packed & 0x07) + 1)
+}
+
+fn strip_gif_metadata(content: &[u8]) -> Result<Vec<u8>, Error> {
+  let mut output = Vec::with_capacity(content.len());
+
+  // Header and logical screen descriptor, followed by the global color table.
+  let mut position = 13 + get_gif_color_table_size(get_byte(content, 10)?);
+  output.extend_from_slice(get_bytes(content, 0, position)?);
+
+  loop {
+    let start = position;
+    match get_byte(content, position)? {
+      0x3B => {
+        output.push(0x3B);
+        return Ok(output);
+      }
+      0x2C => {
+        let packed = get_byte(content, position + 9)?;
+        // Descriptor, local color table and the LZW minimum code size.
+        position += 10 + get_gif_color_table_size(packed) + 1;
+        position = skip_gif_sub_blocks(content, position)?;
+        output.extend_from_slice(&content[start..position]);
+      }
+      0x21 => {
+        let label = get_byte(content, position + 1)?;
+        position = skip_gif_sub_blocks(content, position + 2)?;
+
+        // Comments and application data other than the animation loop count are metadata, like XMP.
+        let is_metadata = match label {
+          0xFE => true,
+          0xFF => {
+            let identifier = get_bytes(content, start + 3, 8)?;
+            identifier != b"NETSCAPE" && identifier != b"ANIMEXTS"
+          }
+          _ => false,
+        };
+        if !is_metadata {
+          output.extend_from_slice(&content[start..position]);
+        }
+      }
+      _ => return Err(malformed()),
+    }
+  }
+}
+
+pub const IMAGE_VARIANT_WIDTHS: &[u32] = &[320, 640, 1280];
+
+// Decoding allocates the full bitmap, so a small file declaring huge dimensions is refused up front.
+const MAX_IMAGE_DIMENSION: u32 = 12_000;
+const MAX_IMAGE_ALLOC_BYTES: u64 = 512 * 1024 * 1024;
+
+const JPEG_QUALITY: u8 = 85;
+const WEBP_QUALITY: f32 = 80.0;
+
+pub struct ImageVariant {
+  pub key: String,
+  pub content: Bytes,
+  pub content_type: String,
+}
+
+pub struct ImageVariants {
+  pub width: u32,
+  pub widths: Vec<u32>,
+  pub content_type: String,
+  pub variants: Vec<ImageVariant>,
+}
+
+pub fn get_image_key(image_id: Uuid, width: Option<u32>) -> String {
+  match width {
+    Some(width) => format!("{}_w{}", image_id, width),
+    None => image_id.to_string(),
+  }
+}
+
+pub fn get_webp_key(image_id: Uuid, width: Option<u32>) -> String {
+  format!("{}.webp", get_image_key(image_id, width))
+}
+
+/// Every object key written for an image: the original, its full-size WebP
+/// rendition and both renditions of each width variant.
+pub fn get_image_keys(image_id: Uuid, widths: &[i32]) -> Vec<String> {
+  let mut keys = vec![get_image_key(image_id, None), get_webp_key(image_id, None)];
+  for width in widths {
+    keys.push(get_image_key(image_id, Some(*width as u32)));
+    keys.push(get_webp_key(image_id, Some(*width as u32)));
+  }
+
+  keys
+}
+
+fn read_image(content: &[u8], format: ImageFormat) -> ImageReader<Cursor<&[u8]>> {
+  let mut limits = Limits::default();
+  limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+  limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+  limits.max_alloc = Some(MAX_IMAGE_ALLOC_BYTES);
+
+  let mut reader = ImageReader::with_format(Cursor::new(content), format);
+  reader.limits(limits);
+  reader
+}
+
+fn map_decoding_error(error: ImageError) -> Error {
+  match error {
+    ImageError::Limits(_) => Error::PayloadTooLarge(
+      format!("Image must not exceed {0}x{0} pixels.", MAX_IMAGE_DIMENSION),
+    ),
+    _ => malformed(),
+  }
+}
+
+fn decode_image(content: &[u8], format: ImageFormat) -> Result<DynamicImage, Error> {
+  read_image(content, format).decode().map_err(map_decoding_error)
+}
+
+fn encode_image(image: &DynamicImage, format: ImageFormat) -> Result<Bytes, Error> {
+  let mut content = Vec::new();
+  let result = match format {
+    ImageFormat::Jpeg => image.to_rgb8().write_with_encoder(JpegEncoder::new_with_quality(&mut content, JPEG_QUALITY)),
+    ImageFormat::Png => image.write_with_encoder(PngEncoder::new(&mut content)),
+    _ => return encode_webp(image),
+  };
+
+  result
+    .map(|_| Bytes::from(content))
+    .map_err(|error| Error::internal("Error while encoding image.", error))
+}
+
+/// Renditions are lossy, a lossless WebP of a photo is larger than the JPEG it was made from.
+fn encode_webp(image: &DynamicImage) -> Result<Bytes, Error> {
+  let rgba = image.to_rgba8();
+
+  webp::Encoder::from_rgba(rgba.as_raw(), rgba.width(), rgba.height())
+    .encode_simple(false, WEBP_QUALITY)
+    .map(|content| Bytes::copy_from_slice(&content))
+    .map_err(|error| Error::internal("Error while encoding image.", format!("{:?}", error)))
+}
+
+/// GIFs are kept as-is, so they have no WebP renditions. An empty content type,
+/// from before it was recorded, counts as having them.
+pub fn has_webp_variants(content_type: &str) -> bool {
+  content_type != "image/gif"
+}
+
+/// Generates the width variants narrower than the original and a WebP
+/// rendition of each, plus a full-size WebP rendition. GIFs are kept as-is so
+/// that animations survive.
+pub fn generate_variants(image_id: Uuid, content: &[u8], content_type: &str) -> Result<ImageVariants, Error> {
+  let format = ImageFormat::from_mime_type(content_type).ok_or_else(malformed)?;
+
+  if !has_webp_variants(content_type) {
+    let (width, _) = read_image(content, format)
+      .into_dimensions()
+      .map_err(map_decoding_error)?;
+
+    return Ok(ImageVariants { width, widths: Vec::new(), content_type: content_type.to_string(), variants: Vec::new() });
+  }
+
+  let image = decode_image(content, format)?;
+
+  let mut variants = vec![ImageVariant {
+    key: get_webp_key(image_id, None),
+    content: encode_image(&image, ImageFormat::WebP)?,
+    content_type: "image/webp".to_string(),
+  }];
+  let mut widths = Vec::new();
+
+  for width in IMAGE_VARIANT_WIDTHS.iter().copied().filter(|width| *width < image.width()) {
+    let resized = image.resize(width, u32::MAX, FilterType::Lanczos3);
+
+    variants.push(ImageVariant {
+      key: get_image_key(image_id, Some(width)),
+      content: encode_image(&resized, format)?,
+      content_type: content_type.to_string(),
+    });
+    variants.push(ImageVariant {
+      key: get_webp_key(image_id, Some(width)),
+      content: encode_image(&resized, ImageFormat::WebP)?,
+      content_type: "image/webp".to_string(),
+    });
+    widths.push(width);
+  }
+
+  Ok(ImageVariants { width: image.width(), widths, content_type: content_type.to_string(), variants })
+}
+
+pub struct ImageTransform {
+  pub width: Option<u32>,
+  pub height: Option<u32>,
+  pub fit: ImageFit,
+  pub format: Option<ImageFormat>,
+}
+
+/// Transformed renditions are cached under a prefix per image, so they can be listed when it is deleted.
+pub fn get_transform_prefix(image_id: Uuid) -> String {
+  format!("{}_transforms/", image_id)
+}
+
+/// Deterministic key under which a transformed rendition is cached.
+pub fn get_transform_key(image_id: Uuid, transform: &ImageTransform) -> String {
+  let size = |value: Option<u32>| value.map_or("auto".to_string(), |value| value.to_string());
+  let key = format!(
+    "{}{}x{}_{}",
+    get_transform_prefix(image_id),
+    size(transform.width),
+    size(transform.height),
+    transform.fit.as_str(),
+  );
+
+  match transform.format {
+    Some(format) => format!("{}.{}", key, format.extensions_str()[0]),
+    None => key,
+  }
+}
+
+pub fn transform_image(content: &[u8], content_type: &str, transform: &ImageTransform) -> Result<(Bytes, String), Error> {
+  let source_format = ImageFormat::from_mime_type(content_type).ok_or_else(malformed)?;
+  let image = decode_image(content, source_format)?;
+
+  let image = match (transform.width, transform.height, transform.fit) {
+    (None, None, _) => image,
+    (Some(width), Some(height), ImageFit::Cover) => image.resize_to_fill(width, height, FilterType::Lanczos3),
+    (Some(width), Some(height), ImageFit::Fill) => image.resize_exact(width, height, FilterType::Lanczos3),
+    // A missing side is bounded by the source, so the image is never scaled up.
+    (width, height, _) => image.resize(
+      width.unwrap_or(image.width()),
+      height.unwrap_or(image.height()),
+      FilterType::Lanczos3,
+    ),
+  };
+
+  let format = match transform.format {
+    Some(format) => format,
+    // Animated GIFs are flattened to a single frame, so keep them lossless.
+    None if source_format == ImageFormat::Gif => ImageFormat::Png,
+    None => source_format,
+  };
+
+  let content = encode_image(&image, format)?;
+
+  Ok((content, format.to_mime_type().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+  use image::RgbImage;
+
+  use super::*;
+
+  const GIF_HEADER: &[u8] = &[
+    b'G', b'I', b'F', b'8', b'9', b'a', 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF,
+  ];
+  const GIF_IMAGE: &[u8] = &[
+    0x2C, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44, 0x01, 0x00, 0x3B,
+  ];
+
+  fn encode(format: ImageFormat) -> Vec<u8> {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 3, image::Rgb([200, 10, 10])));
+    encode_image(&image, format).unwrap().to_vec()
+  }
+
+  fn contains(content: &[u8], needle: &[u8]) -> bool {
+    content.windows(needle.len()).any(|window| window == needle)
+  }
+
+  fn jpeg_segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+    let mut segment = vec![0xFF, marker];
+    segment.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+    segment.extend_from_slice(payload);
+    segment
+  }
+
+  fn png_chunk(chunk_type: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+    chunk.extend_from_slice(chunk_type);
+    chunk.extend_from_slice(data);
+    chunk.extend_from_slice(&[0, 0, 0, 0]);
+    chunk
+  }
+
+  fn webp_chunk(fourcc: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut chunk = fourcc.to_vec();
+    chunk.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    chunk.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+      chunk.push(0);
+    }
+    chunk
+  }
+
+  fn jpeg_with_metadata() -> Vec<u8> {
+    let jpeg = encode(ImageFormat::Jpeg);
+    let mut content = jpeg[0..2].to_vec();
+    content.extend(jpeg_segment(0xE1, b"Exif\0\0GPS-secret"));
+    content.extend(jpeg_segment(0xE1, b"http://ns.adobe.com/xap/1.0/\0<x:xmpmeta/>"));
+    content.extend(jpeg_segment(0xED, b"Photoshop 3.0\0IPTC"));
+    content.extend_from_slice(&jpeg[2..]);
+    content
+  }
+
+  fn png_with_metadata() -> Vec<u8> {
+    let png = encode(ImageFormat::Png);
+    // The signature and IHDR come first.
+    let mut content = png[0..33].to_vec();
+    content.extend(png_chunk(b"eXIf", b"GPS-secret"));
+    content.extend(png_chunk(b"iTXt", b"XML:com.adobe.xmp\0\0\0\0\0<x:xmpmeta/>"));
+    content.extend_from_slice(&png[33..]);
+    content
+  }
+
+  fn webp_with_metadata() -> Vec<u8> {
+    let webp = encode(ImageFormat::WebP);
+    let mut vp8x = vec![WEBP_EXIF_FLAG | WEBP_XMP_FLAG, 0, 0, 0];
+    vp8x.extend_from_slice(&[3, 0, 0, 2, 0, 0]);
+
+    let mut chunks = webp_chunk(b"VP8X", &vp8x);
+    chunks.extend_from_slice(&webp[12..]);
+    chunks.extend(webp_chunk(b"EXIF", b"GPS-secret"));
+    chunks.extend(webp_chunk(b"XMP ", b"<x:xmpmeta/>"));
+
+    let mut content = b"RIFF".to_vec();
+    content.extend_from_slice(&((chunks.len() + 4) as u32).to_le_bytes());
+    content.extend_from_slice(b"WEBP");
+    content.extend(chunks);
+    content
+  }
+
+  fn gif_with_metadata() -> Vec<u8> {
+    let mut content = GIF_HEADER.to_vec();
+    content.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+    content.extend_from_slice(b"NETSCAPE2.0");
+    content.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+    content.extend_from_slice(&[0x21, 0xFE, 0x0A]);
+    content.extend_from_slice(b"GPS-secret");
+    content.push(0x00);
+    content.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+    content.extend_from_slice(b"XMP DataXMP");
+    content.extend_from_slice(&[0x0C]);
+    content.extend_from_slice(b"<x:xmpmeta/>");
+    content.push(0x00);
+    content.extend_from_slice(GIF_IMAGE);
+    content
+  }
+
+  fn assert_stripped(content: Vec<u8>) -> Vec<u8> {
+    assert!(image::load_from_memory(&content).is_ok());
+
+    let (stripped, _) = prepare_image(Bytes::from(content)).unwrap();
+    assert!(!contains(&stripped, b"GPS-secret"));
+    assert!(!contains(&stripped, b"xmpmeta"));
+    assert!(image::load_from_memory(&stripped).is_ok());
+
+    stripped.to_vec()
+  }
+
+  fn transform(content: &[u8], width: Option<u32>, height: Option<u32>, fit: ImageFit) -> DynamicImage {
+    let transform = ImageTransform { width, height, fit, format: Some(ImageFormat::Png) };
+    let (content, content_type) = transform_image(content, "image/jpeg", &transform).unwrap();
+    assert_eq!(content_type, "image/png");
+
+    image::load_from_memory(&content).unwrap()
+  }
+
+  #[test]
+  fn transform_without_size_keeps_dimensions() {
+    let jpeg = encode(ImageFormat::Jpeg);
+
+    for fit in [ImageFit::Contain, ImageFit::Cover, ImageFit::Fill] {
+      let image = transform(&jpeg, None, None, fit);
+      assert_eq!((image.width(), image.height()), (4, 3));
+    }
+  }
+
+  #[test]
+  fn transform_with_one_side_does_not_upscale() {
+    let jpeg = encode(ImageFormat::Jpeg);
+
+    let image = transform(&jpeg, Some(1280), None, ImageFit::Cover);
+    assert_eq!((image.width(), image.height()), (4, 3));
+
+    let image = transform(&jpeg, None, Some(2), ImageFit::Contain);
+    assert_eq!(image.height(), 2);
+    assert!(image.width() <= 4);
+  }
+
+  #[test]
+  fn variants_are_smaller_than_the_source() {
+    // A photo-like gradient with some texture, at a width above every variant.
+    let photo = RgbImage::from_fn(1600, 1000, |x, y| {
+      let noise = ((x * 7 + y * 13) % 17) as u8;
+      image::Rgb([(x / 8) as u8 ^ noise, (y / 5) as u8, ((x + y) / 12) as u8 ^ noise])
+    });
+    let jpeg = encode_image(&DynamicImage::ImageRgb8(photo), ImageFormat::Jpeg).unwrap();
+
+    let generated = generate_variants(Uuid::nil(), &jpeg, "image/jpeg").unwrap();
+    assert_eq!(generated.widths, IMAGE_VARIANT_WIDTHS);
+
+    for variant in generated.variants {
+      assert!(
+        variant.content.len() < jpeg.len(),
+        "{} has {} bytes, the source {}", variant.key, variant.content.len(), jpeg.len(),
+      );
+    }
+  }
+
+  #[test]
+  fn refuses_images_above_the_dimension_limit() {
+    let wide = DynamicImage::ImageRgb8(RgbImage::new(MAX_IMAGE_DIMENSION + 1, 1));
+    let png = encode_image(&wide, ImageFormat::Png).unwrap();
+    assert!(matches!(generate_variants(Uuid::nil(), &png, "image/png"), Err(Error::PayloadTooLarge(_))));
+
+    let transform = ImageTransform { width: Some(320), height: None, fit: ImageFit::Contain, format: None };
+    assert!(matches!(transform_image(&png, "image/png", &transform), Err(Error::PayloadTooLarge(_))));
+
+    // GIFs are not decoded, their declared size is checked instead.
+    let mut gif = GIF_HEADER.to_vec();
+    gif[6..8].copy_from_slice(&u16::MAX.to_le_bytes());
+    gif.extend_from_slice(GIF_IMAGE);
+    assert!(matches!(generate_variants(Uuid::nil(), &gif, "image/gif"), Err(Error::PayloadTooLarge(_))));
+  }
+
+  #[test]
+  fn strips_jpeg_exif_xmp_and_iptc() {
+    let stripped = assert_stripped(jpeg_with_metadata());
+
+    assert!(!contains(&stripped, b"Photoshop"));
+  }
+
+  #[test]
+  fn strips_png_exif_and_xmp() {
+    let stripped = assert_stripped(png_with_metadata());
+
+    assert!(!contains(&stripped, b"eXIf"));
+  }
+
+  #[test]
+  fn strips_webp_exif_and_xmp() {
+    let stripped = assert_stripped(webp_with_metadata());
+
+    assert!(!contains(&stripped, b"EXIF"));
+    assert_eq!(stripped[20] & (WEBP_EXIF_FLAG | WEBP_XMP_FLAG), 0);
+  }
+
+  #[test]
+  fn strips_gif_comments_and_xmp_but_keeps_loop_count() {
+    let stripped = assert_stripped(gif_with_metadata());
+
+    assert!(contains(&stripped, b"NETSCAPE2.0"));
+  }
+
+  #[test]
+  fn truncated_images_do_not_panic() {
+    for content in [jpeg_with_metadata(), png_with_metadata(), webp_with_metadata(), gif_with_metadata()] {
+      for length in 0..content.len() {
+        let _ = prepare_image(Bytes::copy_from_slice(&content[..length]));
+      }
+    }
+  }
+
+  #[test]
+  fn rejects_malformed_images() {
+    // A JPEG segment running past the end of the file.
+    let mut jpeg = vec![0xFF, 0xD8];
+    jpeg.extend(jpeg_segment(0xE0, b"JFIF"));
+    jpeg.extend_from_slice(&[0xFF, 0xDB, 0xFF, 0xFF, 0x00]);
+    assert!(strip_jpeg_metadata(&jpeg).is_err());
+
+    // A JPEG segment with a length below its own size.
+    assert!(strip_jpeg_metadata(&[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x01]).is_err());
+
+    // Garbage instead of the next JPEG marker.
+    assert!(strip_jpeg_metadata(&[0xFF, 0xD8, 0x00, 0x00]).is_err());
+
+    // A PNG chunk whose length would overflow.
+    let mut png = PNG_SIGNATURE.to_vec();
+    png.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF]);
+    png.extend_from_slice(b"IHDR");
+    assert!(strip_png_metadata(&png).is_err());
+
+    // A WebP chunk longer than the file.
+    let mut webp = b"RIFF\0\0\0\0WEBP".to_vec();
+    webp.extend_from_slice(b"VP8L");
+    webp.extend_from_slice(&100u32.to_le_bytes());
+    webp.extend_from_slice(&[0; 10]);
+    assert!(strip_webp_metadata(&webp).is_err());
+
+    // A GIF without a trailer and one with an unknown block.
+    assert!(strip_gif_metadata(&gif_with_metadata()[..GIF_HEADER.len() + 20]).is_err());
+    let mut gif = GIF_HEADER.to_vec();
+    gif.push(0x00);
+    assert!(strip_gif_metadata(&gif).is_err());
+  }
+}
@@ -0,0 +1,22 @@
+use uuid::Uuid;
+
+// Post bodies point at their media with `media:<id>`, e.g. `![Sunset](media:<id>)`.
+const MEDIA_REFERENCE_PREFIX: &str = "media:";
+const UUID_LENGTH: usize = 36;
+
+pub fn get_media_references(body: &str) -> Vec<Uuid> {
+  let mut references: Vec<Uuid> = Vec::new();
+
+  for (index, _) in body.match_indices(MEDIA_REFERENCE_PREFIX) {
+    let start = index + MEDIA_REFERENCE_PREFIX.len();
+    let id = body.get(start..start + UUID_LENGTH).and_then(|id| Uuid::parse_str(id).ok());
+
+    if let Some(id) = id {
+      if !references.contains(&id) {
+        references.push(id);
+      }
+    }
+  }
+
+  references
+}
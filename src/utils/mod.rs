@@ -1,5 +1,15 @@
+pub mod archive_util;
 pub mod file_util;
+pub mod http_util;
+pub mod image_util;
 pub mod mapper_util;
+pub mod media_util;
 pub mod password_util;
+pub mod permission_util;
 pub mod request_util;
-pub mod token_util;
\ No newline at end of file
+pub mod serde_util;
+pub mod shutdown_util;
+pub mod store_key_util;
+pub mod token_util;
+pub mod trace_util;
+pub mod validation_util;
@@ -0,0 +1,14 @@
+use uuid::Uuid;
+
+use crate::enums::error::Error;
+use crate::enums::user_role::UserRole;
+use crate::models::token_claims::TokenClaims;
+
+/// Posts and comments can only be changed by their author, administrators may change any of them.
+pub fn check_owner(claims: &TokenClaims, user_id: Uuid) -> Result<(), Error> {
+  if claims.sub != user_id && claims.role != UserRole::Admin.as_str() {
+    return Err(Error::Forbidden("Only the author or an administrator can change this.".to_string()));
+  }
+
+  Ok(())
+}
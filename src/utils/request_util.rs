@@ -1,3 +1,4 @@
+use axum::http::{HeaderMap, HeaderName};
 use serde::de::DeserializeOwned;
 
 pub async fn map_body_to_model<DTO, MODEL>(
@@ -8,4 +9,10 @@ pub async fn map_body_to_model<DTO, MODEL>(
         DTO: DeserializeOwned,
 {
     mapper(req)
+}
+
+pub fn get_header(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers.get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
 }
\ No newline at end of file
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Deserializer};
+
+/// Used with `#[serde(default)]` on `Option<Option<T>>` fields so an explicit `null`
+/// (`Some(None)`) can be told apart from an absent field (`None`), as JSON Merge Patch requires.
+pub fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+  where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+  Deserialize::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,32 @@
+use uuid::Uuid;
+
+// Archives are only handed out through their download link, never by the public proxy.
+pub const EXPORT_KEY_PREFIX: &str = "exports/";
+// Direct uploads are unchecked client content until they are confirmed and copied to their image key.
+pub const UPLOAD_KEY_PREFIX: &str = "uploads/";
+
+const PRIVATE_KEY_PREFIXES: [&str; 2] = [EXPORT_KEY_PREFIX, UPLOAD_KEY_PREFIX];
+
+pub fn get_export_key(object_id: Uuid) -> String {
+  format!("{}{}", EXPORT_KEY_PREFIX, object_id)
+}
+
+pub fn get_upload_key(upload_id: Uuid) -> String {
+  format!("{}{}", UPLOAD_KEY_PREFIX, upload_id)
+}
+
+pub fn is_private_key(key: &str) -> bool {
+  PRIVATE_KEY_PREFIXES.iter().any(|prefix| key.starts_with(prefix))
+}
+
+/// Every key is derived from the id of the object it belongs to, after an optional private prefix and
+/// followed by an optional `_<variant>` or `.<ext>` suffix.
+pub fn get_object_id(key: &str) -> Option<Uuid> {
+  let key = PRIVATE_KEY_PREFIXES
+    .iter()
+    .find_map(|prefix| key.strip_prefix(prefix))
+    .unwrap_or(key);
+  let end = key.find(['_', '.']).unwrap_or(key.len());
+
+  Uuid::parse_str(&key[..end]).ok()
+}
@@ -8,7 +8,7 @@ pub fn get_bearer_token(auth_header: String) -> Result<String, Error> {
   if parts.len() == 2 && parts[0] == "Bearer" {
     Ok(parts[1].to_string())
   } else {
-    Err(Error::InternalServerError("Bearer token was not found inside Authentication header.".to_string()))
+    Err(Error::Unauthorized("Bearer token was not found inside Authentication header.".to_string()))
   }
 }
 
@@ -17,6 +17,6 @@ pub fn parse_jwt_token(token_string: String, secret: String) -> Result<TokenClai
 
   match decode::<TokenClaims>(&token_string, &DecodingKey::from_secret(secret.as_bytes()), &validation) {
     Ok(token_data) => Ok(token_data.claims),
-    Err(_) => Err(Error::InternalServerError("Error while decoding jwt token.".to_string())),
+    Err(_) => Err(Error::Unauthorized("Error while decoding jwt token.".to_string())),
   }
 }
\ No newline at end of file
@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+
+use axum::http::HeaderMap;
+use opentelemetry::global;
+use opentelemetry_http::HeaderExtractor;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Continues the trace of an incoming W3C `traceparent` header, when export is enabled.
+pub fn set_remote_parent(span: &Span, headers: &HeaderMap) {
+  let context = global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)));
+  // Fails only when no OpenTelemetry layer is installed, the span is then not exported anyway.
+  let _ = span.set_parent(context);
+}
+
+/// Headers that pass the trace of `span` on to an outbound request.
+pub fn propagation_headers(span: &Span) -> HashMap<String, String> {
+  let mut headers = HashMap::new();
+  global::get_text_map_propagator(|propagator| propagator.inject_context(&span.context(), &mut headers));
+
+  headers
+}
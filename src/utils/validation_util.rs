@@ -0,0 +1,77 @@
+use crate::enums::error::Error;
+use crate::models::field_error::FieldError;
+
+// RFC 5321 limit on the length of an address.
+const MAX_EMAIL_LENGTH: usize = 254;
+
+/// Collects field errors so a DTO can report all of them in one response.
+#[derive(Debug, Default)]
+pub struct Validator {
+  errors: Vec<FieldError>,
+}
+
+impl Validator {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn add(&mut self, field: &str, code: &str, message: &str) -> &mut Self {
+    self.errors.push(FieldError::new(field, code, message));
+    self
+  }
+
+  /// Rejects empty and whitespace-only values.
+  pub fn required(&mut self, field: &str, value: &str) -> &mut Self {
+    if value.trim().is_empty() {
+      self.add(field, "required", &format!("{} is required.", field));
+    }
+    self
+  }
+
+  /// Checks the length in characters, not bytes.
+  pub fn max_length(&mut self, field: &str, value: &str, max: usize) -> &mut Self {
+    if value.chars().count() > max {
+      self.add(field, "too_long", &format!("{} must be at most {} characters long.", field, max));
+    }
+    self
+  }
+
+  /// Rejects an explicit `null` in a merge patch for a field that cannot be cleared.
+  pub fn not_null<T>(&mut self, field: &str, value: &Option<Option<T>>) -> &mut Self {
+    if matches!(value, Some(None)) {
+      self.add(field, "not_nullable", &format!("{} cannot be null.", field));
+    }
+    self
+  }
+
+  pub fn email(&mut self, field: &str, value: &str) -> &mut Self {
+    if !value.trim().is_empty() && !is_email(value) {
+      self.add(field, "invalid_email", &format!("{} must be a valid email address.", field));
+    }
+    self
+  }
+
+  pub fn finish(&mut self) -> Result<(), Error> {
+    if self.errors.is_empty() {
+      return Ok(());
+    }
+
+    Err(Error::Validation(std::mem::take(&mut self.errors)))
+  }
+}
+
+fn is_email(value: &str) -> bool {
+  if value.len() > MAX_EMAIL_LENGTH || value.chars().any(char::is_whitespace) {
+    return false;
+  }
+
+  match value.split_once('@') {
+    Some((local, domain)) => {
+      !local.is_empty()
+        && !domain.contains('@')
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|label| !label.is_empty())
+    }
+    None => false,
+  }
+}
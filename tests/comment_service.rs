@@ -0,0 +1,67 @@
+mod support;
+
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use rust_blog::dtos::request::comment_patch_request_dto::CommentPatchRequestDto;
+use rust_blog::dtos::request::comment_request_dto::CommentRequestDto;
+use rust_blog::enums::user_role::UserRole;
+use rust_blog::mappers::comment_mapper::{from_dto_to_comment, from_dto_to_comment_patch};
+use rust_blog::models::comment::Comment;
+use rust_blog::models::comment_patch::CommentPatch;
+use rust_blog::services::comment_service::CommentService;
+use rust_blog::services::post_service::PostService;
+use rust_blog::traits::entity_tag::EntityTag;
+
+use support::{claims, new_post, Blog};
+
+fn comment_request(post_id: Uuid, body: &str) -> Comment {
+  from_dto_to_comment(&CommentRequestDto { body: body.to_string(), post_id })
+}
+
+fn patch_request(json: &str) -> CommentPatch {
+  from_dto_to_comment_patch(&serde_json::from_str::<CommentPatchRequestDto>(json).unwrap())
+}
+
+#[tokio::test]
+async fn comments_are_changed_only_under_their_post_by_their_author() {
+  let blog = Blog::new();
+  let author = Uuid::new_v4();
+  let post = blog.posts.create(new_post(author)).await.unwrap();
+  let other_post = blog.posts.create(new_post(author)).await.unwrap();
+
+  let mut request = comment_request(post.id, "First");
+  request.user_id = author;
+  let comment = blog.comments.create(request).await.unwrap();
+  let author_claims = claims(author, UserRole::User);
+
+  let error = blog.comments
+    .patch(&author_claims, other_post.id, comment.id, patch_request(r#"{"body": "Moved"}"#), None)
+    .await
+    .unwrap_err();
+  assert_eq!(error.code(), StatusCode::NOT_FOUND);
+
+  let mut request = comment_request(other_post.id, "Moved");
+  request.id = comment.id;
+  let error = blog.comments.update(&author_claims, request, None).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::NOT_FOUND);
+
+  let stranger = claims(Uuid::new_v4(), UserRole::User);
+  let error = blog.comments
+    .patch(&stranger, post.id, comment.id, patch_request(r#"{"body": "Taken"}"#), None)
+    .await
+    .unwrap_err();
+  assert_eq!(error.code(), StatusCode::FORBIDDEN);
+
+  let error = blog.comments
+    .patch(&author_claims, post.id, comment.id, patch_request(r#"{"body": "Stale"}"#), Some("\"0\"".to_string()))
+    .await
+    .unwrap_err();
+  assert_eq!(error.code(), StatusCode::PRECONDITION_FAILED);
+
+  let patched = blog.comments
+    .patch(&author_claims, post.id, comment.id, patch_request("{}"), Some(comment.entity_tag()))
+    .await
+    .unwrap();
+  assert_eq!((patched.post_id, patched.body.as_str()), (post.id, "First"));
+}
@@ -0,0 +1,92 @@
+mod support;
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use rust_blog::enums::export_status::ExportStatus;
+use rust_blog::enums::user_role::UserRole;
+use rust_blog::models::user::CreateUser;
+use rust_blog::repository::export_repository::ExportRepository;
+use rust_blog::repository::user_repository::UserRepository;
+use rust_blog::services::export_service::{ExportService, ExportServiceImpl};
+use rust_blog::services::post_service::PostService;
+use rust_blog::services::user_service::UserServiceImpl;
+use rust_blog::utils::store_key_util::{get_export_key, is_private_key};
+
+use support::{new_post, Blog, MemoryExportRepository, MemoryUserRepository};
+
+struct Exports {
+  service: ExportServiceImpl,
+  repo: Arc<MemoryExportRepository>,
+  user_id: Uuid,
+}
+
+/// An export service for a user with one post.
+async fn exports() -> Exports {
+  let blog = Blog::new();
+  let user_repo = Arc::new(MemoryUserRepository::default());
+  let repo = Arc::new(MemoryExportRepository::default());
+
+  let user = user_repo
+    .create(CreateUser {
+      email: "jane@example.com".to_string(),
+      username: "jane".to_string(),
+      password: "hash".to_string(),
+      role: UserRole::User.as_str().to_string(),
+    })
+    .await
+    .unwrap();
+  blog.posts.create(new_post(user.id)).await.unwrap();
+
+  let service = ExportServiceImpl::new(
+    repo.clone(),
+    Arc::new(UserServiceImpl::new(user_repo)),
+    blog.posts,
+    blog.comments,
+    blog.store,
+  );
+
+  Exports { service, repo, user_id: user.id }
+}
+
+#[tokio::test]
+async fn download_requires_the_token_of_a_ready_export() {
+  let exports = exports().await;
+  let requested = exports.service.request(exports.user_id).await.unwrap();
+  exports.service.drain().await;
+
+  let export = exports.service.get_one(exports.user_id, requested.id).await.unwrap();
+  assert_eq!(export.status, ExportStatus::Ready.as_str());
+  // The archive is kept under a key the public proxy refuses to serve.
+  assert!(is_private_key(&get_export_key(export.object_id.unwrap())));
+
+  let error = exports.service.download(export.id, Uuid::new_v4()).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::NOT_FOUND);
+  let error = exports.service.download(Uuid::new_v4(), export.download_token).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::NOT_FOUND);
+  let error = exports.service.get_one(Uuid::new_v4(), export.id).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::NOT_FOUND);
+
+  let archive = exports.service.download(export.id, export.download_token).await.unwrap();
+  assert!(archive.starts_with(b"PK"));
+
+  exports.repo.modify(export.id, |export| export.expires_at = Some(Utc::now() - Duration::seconds(1)));
+  let error = exports.service.download(export.id, export.download_token).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::GONE);
+}
+
+#[tokio::test]
+async fn a_pending_export_is_reused() {
+  let exports = exports().await;
+  let pending = exports.repo.create(exports.user_id).await.unwrap().unwrap();
+
+  let requested = exports.service.request(exports.user_id).await.unwrap();
+  assert_eq!(requested.id, pending.id);
+
+  // Its token is issued up front, but nothing can be downloaded before the archive is built.
+  let error = exports.service.download(pending.id, pending.download_token).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::{Body, Bytes};
+use axum::http::{Request, StatusCode};
+use axum::routing::{get, post};
+use tokio::sync::mpsc;
+use tower::ServiceExt;
+use tower_http::trace::TraceLayer;
+
+use rust_blog::config::logging::init_logging;
+use rust_blog::config::settings::Settings;
+use rust_blog::config::telemetry::init_tracer_provider;
+use rust_blog::middlewares::trace_middleware::{make_request_span, record_response_status};
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+/// Stands in for the collector, every body posted to `/v1/traces` is sent to the channel.
+async fn start_collector() -> (String, mpsc::UnboundedReceiver<Bytes>) {
+  let (sender, receiver) = mpsc::unbounded_channel();
+  let collector = Router::new().route("/v1/traces", post(move |body: Bytes| async move {
+    let _ = sender.send(body);
+    StatusCode::OK
+  }));
+
+  let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  tokio::spawn(async move { axum::serve(listener, collector).await.unwrap() });
+
+  (format!("http://{}", addr), receiver)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn request_span_continues_the_incoming_trace() {
+  let (endpoint, mut exported) = start_collector().await;
+  let settings = Settings { otlp_endpoint: endpoint, ..Settings::default() };
+
+  let provider = init_tracer_provider(&settings).expect("OTLP export is enabled");
+  init_logging(&settings, Some(&provider));
+
+  let app = Router::new()
+    .route("/ping", get(|| async { "pong" }))
+    .layer(TraceLayer::new_for_http().make_span_with(make_request_span).on_response(record_response_status));
+  let request = Request::get("/ping").header("traceparent", TRACEPARENT).body(Body::empty()).unwrap();
+  let response = app.oneshot(request).await.unwrap();
+  assert_eq!(response.status(), StatusCode::OK);
+  // The request span closes with the response body.
+  drop(response);
+
+  tokio::task::spawn_blocking(move || provider.shutdown()).await.unwrap().unwrap();
+
+  // Trace ids are raw bytes in the protobuf payload.
+  let trace_id: Vec<u8> = (0..TRACE_ID.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(&TRACE_ID[i..i + 2], 16).unwrap())
+    .collect();
+  let body = tokio::time::timeout(Duration::from_secs(5), exported.recv())
+    .await
+    .expect("no spans were exported")
+    .unwrap();
+  assert!(body.windows(trace_id.len()).any(|window| window == trace_id.as_slice()));
+}
@@ -0,0 +1,175 @@
+mod support;
+
+use axum::body::Bytes;
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use rust_blog::dtos::request::post_patch_request_dto::PostPatchRequestDto;
+use rust_blog::dtos::request::post_request_dto::PostRequestDto;
+use rust_blog::enums::user_role::UserRole;
+use rust_blog::mappers::post_mapper::{from_dto_to_post, from_dto_to_post_patch};
+use rust_blog::models::post::Post;
+use rust_blog::repository::image_upload_repository::ImageUploadRepository;
+use rust_blog::services::post_service::PostService;
+use rust_blog::services::store_service::StoreService;
+use rust_blog::traits::entity_tag::EntityTag;
+use rust_blog::traits::validate::Validate;
+use rust_blog::utils::image_util::get_image_key;
+use rust_blog::utils::store_key_util::get_upload_key;
+
+use support::{claims, new_post, png, Blog};
+
+const MAX_SIZE: usize = 1024 * 1024;
+
+async fn create_post(blog: &Blog, user_id: Uuid) -> Post {
+  blog.posts.create(new_post(user_id)).await.unwrap()
+}
+
+/// Issues an upload for the post and stores `content` under its key, as the client would.
+async fn upload(blog: &Blog, post_id: Uuid, content: Bytes) -> Uuid {
+  let key = blog.upload_repo.create(post_id, Utc::now() + Duration::minutes(30)).await.unwrap();
+  blog.store.save_with_key(get_upload_key(key), content, "image/png".to_string()).await.unwrap();
+
+  key
+}
+
+fn update_request(id: Uuid, title: &str) -> Post {
+  let mut post = from_dto_to_post(&PostRequestDto { title: title.to_string(), body: "Body".to_string() });
+  post.id = id;
+
+  post
+}
+
+fn patch_request(json: &str) -> PostPatchRequestDto {
+  serde_json::from_str(json).unwrap()
+}
+
+#[tokio::test]
+async fn confirm_accepts_only_uploads_issued_for_the_post() {
+  let blog = Blog::new();
+  let author = Uuid::new_v4();
+  let claims = claims(author, UserRole::User);
+  let post = create_post(&blog, author).await;
+  let other_post = create_post(&blog, author).await;
+  let key = upload(&blog, post.id, png(8, 6)).await;
+
+  let error = blog.posts.confirm_image_upload(&claims, other_post.id, key, MAX_SIZE).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::NOT_FOUND);
+  let error = blog.posts.confirm_image_upload(&claims, post.id, Uuid::new_v4(), MAX_SIZE).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::NOT_FOUND);
+
+  let confirmed = blog.posts.confirm_image_upload(&claims, post.id, key, MAX_SIZE).await.unwrap();
+  assert_eq!(confirmed.image_id, key);
+  // The checked image is served from the image key, the upload itself is queued for deletion.
+  assert!(blog.store.head(get_image_key(key, None)).await.is_ok());
+  assert!(blog.post_repo.deleted_keys.lock().unwrap().contains(&get_upload_key(key)));
+
+  let error = blog.posts.confirm_image_upload(&claims, post.id, key, MAX_SIZE).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn confirm_removes_rejected_uploads() {
+  let blog = Blog::new();
+  let author = Uuid::new_v4();
+  let claims = claims(author, UserRole::User);
+  let post = create_post(&blog, author).await;
+
+  let content = png(8, 6);
+  let key = upload(&blog, post.id, content.clone()).await;
+  let error = blog.posts.confirm_image_upload(&claims, post.id, key, content.len() - 1).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::PAYLOAD_TOO_LARGE);
+  assert!(blog.store.head(get_upload_key(key)).await.is_err());
+
+  let key = upload(&blog, post.id, Bytes::from_static(b"<html><script>alert(1)</script></html>")).await;
+  let error = blog.posts.confirm_image_upload(&claims, post.id, key, MAX_SIZE).await.unwrap_err();
+  assert!(error.code().is_client_error());
+  assert!(blog.store.head(get_upload_key(key)).await.is_err());
+
+  // A rejected upload is used up, it cannot be replaced and confirmed again.
+  let error = blog.posts.confirm_image_upload(&claims, post.id, key, MAX_SIZE).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::NOT_FOUND);
+  assert_eq!(blog.posts.get_one(post.id).await.unwrap().image_id, Uuid::nil());
+}
+
+#[tokio::test]
+async fn changes_require_a_matching_if_match_tag() {
+  let blog = Blog::new();
+  let author = Uuid::new_v4();
+  let claims = claims(author, UserRole::User);
+  let post = create_post(&blog, author).await;
+  let tag = post.entity_tag();
+
+  let error = blog.posts.update(&claims, update_request(post.id, "Stale"), Some("\"0\"".to_string())).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::PRECONDITION_FAILED);
+
+  let updated = blog.posts.update(&claims, update_request(post.id, "Fresh"), Some(tag.clone())).await.unwrap();
+  assert_eq!(updated.title, "Fresh");
+  assert_ne!(updated.entity_tag(), tag);
+
+  // The tag fetched before the update no longer matches anything.
+  let patch = from_dto_to_post_patch(&patch_request(r#"{"body": "Lost"}"#));
+  let error = blog.posts.patch(&claims, post.id, patch, Some(tag.clone())).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::PRECONDITION_FAILED);
+  let error = blog.posts.delete(&claims, post.id, Some(tag)).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::PRECONDITION_FAILED);
+  let error = blog.posts.add_media(&claims, post.id, png(8, 6), Some("\"0\"".to_string())).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::PRECONDITION_FAILED);
+  assert!(blog.posts.get_media(post.id).await.unwrap().is_empty());
+
+  blog.posts.delete(&claims, post.id, Some(updated.entity_tag())).await.unwrap();
+  let error = blog.posts.get_one(post.id).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn merge_patch_null_clears_the_image_and_absent_fields_are_kept() {
+  let blog = Blog::new();
+  let author = Uuid::new_v4();
+  let claims = claims(author, UserRole::User);
+  let post = create_post(&blog, author).await;
+  let image_id = blog.posts.add_image(&claims, post.id, png(8, 6)).await.unwrap().image_id;
+
+  let body_patch = patch_request(r#"{"body": "New body"}"#);
+  body_patch.validate().unwrap();
+  let patched = blog.posts.patch(&claims, post.id, from_dto_to_post_patch(&body_patch), None).await.unwrap();
+  assert_eq!((patched.title.as_str(), patched.body.as_str()), ("Title", "New body"));
+  assert_eq!(patched.image_id, image_id);
+
+  let image_patch = patch_request(r#"{"image": null}"#);
+  image_patch.validate().unwrap();
+  let patched = blog.posts.patch(&claims, post.id, from_dto_to_post_patch(&image_patch), None).await.unwrap();
+  assert_eq!(patched.image_id, Uuid::nil());
+  assert_eq!((patched.title.as_str(), patched.body.as_str()), ("Title", "New body"));
+  assert!(blog.post_repo.deleted_keys.lock().unwrap().contains(&get_image_key(image_id, None)));
+
+  // Title and body cannot be cleared, and an image can only be removed through the patch.
+  for json in [r#"{"title": null}"#, r#"{"body": null}"#, r#"{"image": "other"}"#] {
+    let error = patch_request(json).validate().unwrap_err();
+    assert_eq!(error.code(), StatusCode::UNPROCESSABLE_ENTITY, "{}", json);
+  }
+}
+
+#[tokio::test]
+async fn only_the_author_or_an_admin_can_change_a_post() {
+  let blog = Blog::new();
+  let author = Uuid::new_v4();
+  let post = create_post(&blog, author).await;
+  let stranger = claims(Uuid::new_v4(), UserRole::User);
+  let admin = claims(Uuid::new_v4(), UserRole::Admin);
+
+  let error = blog.posts.update(&stranger, update_request(post.id, "Taken"), None).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::FORBIDDEN);
+  let error = blog.posts.add_image(&stranger, post.id, png(8, 6)).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::FORBIDDEN);
+  let key = upload(&blog, post.id, png(8, 6)).await;
+  let error = blog.posts.confirm_image_upload(&stranger, post.id, key, MAX_SIZE).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::FORBIDDEN);
+  let error = blog.posts.delete(&stranger, post.id, None).await.unwrap_err();
+  assert_eq!(error.code(), StatusCode::FORBIDDEN);
+
+  let updated = blog.posts.update(&admin, update_request(post.id, "Moderated"), None).await.unwrap();
+  assert_eq!(updated.title, "Moderated");
+  assert_eq!(updated.user_id, author);
+}
@@ -0,0 +1,480 @@
+// Each test crate uses only some of the fakes.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use chrono::{DateTime, Utc};
+use image::{DynamicImage, ImageFormat, RgbImage};
+use uuid::Uuid;
+
+use rust_blog::config::metrics::init_metrics;
+use rust_blog::enums::error::Error;
+use rust_blog::enums::export_status::ExportStatus;
+use rust_blog::enums::user_role::UserRole;
+use rust_blog::models::comment::Comment;
+use rust_blog::models::export::Export;
+use rust_blog::models::post::Post;
+use rust_blog::models::post_media::PostMedia;
+use rust_blog::models::token_claims::TokenClaims;
+use rust_blog::models::user::{CreateUser, User};
+use rust_blog::repository::comment_repository::CommentRepository;
+use rust_blog::repository::export_repository::ExportRepository;
+use rust_blog::repository::image_upload_repository::ImageUploadRepository;
+use rust_blog::repository::post_media_repository::PostMediaRepository;
+use rust_blog::repository::post_repository::PostRepository;
+use rust_blog::repository::user_repository::UserRepository;
+use rust_blog::services::comment_service::CommentServiceImpl;
+use rust_blog::services::memory_store_service::MemoryStoreServiceImpl;
+use rust_blog::services::pending_deletion_service::PendingDeletionService;
+use rust_blog::services::post_service::PostServiceImpl;
+
+fn not_found() -> Error {
+  Error::NotFound("Not found.".to_string())
+}
+
+fn conflict() -> Error {
+  Error::Conflict("The resource was modified concurrently.".to_string())
+}
+
+/// A timestamp after `previous`, so every write changes the entity tag.
+fn next_timestamp(previous: DateTime<Utc>) -> DateTime<Utc> {
+  Utc::now().max(previous + chrono::Duration::microseconds(1))
+}
+
+pub fn claims(user_id: Uuid, role: UserRole) -> TokenClaims {
+  TokenClaims {
+    iss: "rustblog".to_string(),
+    sub: user_id,
+    jti: Uuid::new_v4(),
+    iat: 0,
+    exp: 0,
+    is_refresh: false,
+    role: role.as_str().to_string(),
+  }
+}
+
+pub fn png(width: u32, height: u32) -> Bytes {
+  let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, image::Rgb([200, 10, 10])));
+  let mut content = Cursor::new(Vec::new());
+  image.write_to(&mut content, ImageFormat::Png).unwrap();
+
+  Bytes::from(content.into_inner())
+}
+
+pub fn copy_post(post: &Post) -> Post {
+  Post {
+    id: post.id,
+    title: post.title.clone(),
+    body: post.body.clone(),
+    image_id: post.image_id,
+    image_width: post.image_width,
+    image_widths: post.image_widths.clone(),
+    image_content_type: post.image_content_type.clone(),
+    user_id: post.user_id,
+    created_at: post.created_at,
+    updated_at: post.updated_at,
+    media: post.media.clone(),
+  }
+}
+
+pub fn new_post(user_id: Uuid) -> Post {
+  Post {
+    id: Uuid::nil(),
+    title: "Title".to_string(),
+    body: "Body".to_string(),
+    image_id: Uuid::nil(),
+    image_width: 0,
+    image_widths: Vec::new(),
+    image_content_type: String::new(),
+    user_id,
+    created_at: Default::default(),
+    updated_at: Default::default(),
+    media: Vec::new(),
+  }
+}
+
+fn copy_comment(comment: &Comment) -> Comment {
+  Comment {
+    id: comment.id,
+    body: comment.body.clone(),
+    user_id: comment.user_id,
+    post_id: comment.post_id,
+    created_at: comment.created_at,
+    updated_at: comment.updated_at,
+  }
+}
+
+/// Posts kept in memory. Keys queued for deletion are recorded, as the database queues them.
+#[derive(Default)]
+pub struct MemoryPostRepository {
+  posts: Mutex<HashMap<Uuid, Post>>,
+  pub deleted_keys: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl PostRepository for MemoryPostRepository {
+  async fn get_all(&self) -> Result<Vec<Post>, Error> {
+    Ok(self.posts.lock().unwrap().values().map(copy_post).collect())
+  }
+
+  async fn get_all_for_user(&self, user_id: Uuid) -> Result<Vec<Post>, Error> {
+    Ok(self.posts.lock().unwrap().values().filter(|post| post.user_id == user_id).map(copy_post).collect())
+  }
+
+  async fn get_one(&self, id: Uuid) -> Result<Post, Error> {
+    self.posts.lock().unwrap().get(&id).map(copy_post).ok_or_else(not_found)
+  }
+
+  async fn create(&self, mut post: Post) -> Result<Post, Error> {
+    post.id = Uuid::new_v4();
+    post.created_at = Utc::now();
+    post.updated_at = post.created_at;
+    self.posts.lock().unwrap().insert(post.id, copy_post(&post));
+
+    Ok(post)
+  }
+
+  async fn update(&self, mut post: Post, deleted_keys: Vec<String>) -> Result<Post, Error> {
+    let mut posts = self.posts.lock().unwrap();
+    let stored = posts.get(&post.id).ok_or_else(not_found)?;
+    if stored.updated_at != post.updated_at {
+      return Err(conflict());
+    }
+
+    post.updated_at = next_timestamp(post.updated_at);
+    post.media = Vec::new();
+    posts.insert(post.id, copy_post(&post));
+    self.deleted_keys.lock().unwrap().extend(deleted_keys);
+
+    Ok(post)
+  }
+
+  async fn delete(&self, id: Uuid, updated_at: DateTime<Utc>, deleted_keys: Vec<String>) -> Result<(), Error> {
+    let mut posts = self.posts.lock().unwrap();
+    let stored = posts.get(&id).ok_or_else(not_found)?;
+    if stored.updated_at != updated_at {
+      return Err(conflict());
+    }
+
+    posts.remove(&id);
+    self.deleted_keys.lock().unwrap().extend(deleted_keys);
+
+    Ok(())
+  }
+}
+
+#[derive(Default)]
+pub struct MemoryPostMediaRepository {
+  media: Mutex<Vec<PostMedia>>,
+}
+
+#[async_trait]
+impl PostMediaRepository for MemoryPostMediaRepository {
+  async fn get_all_for_posts(&self, post_ids: &[Uuid]) -> Result<Vec<PostMedia>, Error> {
+    let mut media: Vec<PostMedia> = self.media
+      .lock()
+      .unwrap()
+      .iter()
+      .filter(|media| post_ids.contains(&media.post_id))
+      .cloned()
+      .collect();
+    media.sort_by_key(|media| media.position);
+
+    Ok(media)
+  }
+
+  async fn get_one(&self, post_id: Uuid, id: Uuid) -> Result<PostMedia, Error> {
+    self.media
+      .lock()
+      .unwrap()
+      .iter()
+      .find(|media| media.post_id == post_id && media.id == id)
+      .cloned()
+      .ok_or_else(not_found)
+  }
+
+  async fn create(&self, mut media: PostMedia) -> Result<PostMedia, Error> {
+    let mut all = self.media.lock().unwrap();
+    media.id = Uuid::new_v4();
+    media.position = all.iter().filter(|other| other.post_id == media.post_id).count() as i32;
+    media.created_at = Utc::now();
+    media.updated_at = media.created_at;
+    all.push(media.clone());
+
+    Ok(media)
+  }
+
+  async fn update(&self, mut media: PostMedia) -> Result<PostMedia, Error> {
+    let mut all = self.media.lock().unwrap();
+    let stored = all.iter_mut().find(|other| other.id == media.id).ok_or_else(not_found)?;
+    media.updated_at = next_timestamp(stored.updated_at);
+    *stored = media.clone();
+
+    Ok(media)
+  }
+
+  async fn update_positions(&self, post_id: Uuid, ids: &[Uuid]) -> Result<(), Error> {
+    for media in self.media.lock().unwrap().iter_mut().filter(|media| media.post_id == post_id) {
+      media.position = ids.iter().position(|id| *id == media.id).ok_or_else(not_found)? as i32;
+      media.updated_at = next_timestamp(media.updated_at);
+    }
+
+    Ok(())
+  }
+
+  async fn delete(&self, id: Uuid, _deleted_keys: Vec<String>) -> Result<(), Error> {
+    self.media.lock().unwrap().retain(|media| media.id != id);
+
+    Ok(())
+  }
+}
+
+struct Upload {
+  post_id: Uuid,
+  expires_at: DateTime<Utc>,
+  used: bool,
+}
+
+#[derive(Default)]
+pub struct MemoryImageUploadRepository {
+  uploads: Mutex<HashMap<Uuid, Upload>>,
+}
+
+#[async_trait]
+impl ImageUploadRepository for MemoryImageUploadRepository {
+  async fn create(&self, post_id: Uuid, expires_at: DateTime<Utc>) -> Result<Uuid, Error> {
+    let id = Uuid::new_v4();
+    self.uploads.lock().unwrap().insert(id, Upload { post_id, expires_at, used: false });
+
+    Ok(id)
+  }
+
+  async fn is_pending(&self, post_id: Uuid, id: Uuid) -> Result<bool, Error> {
+    Ok(matches!(
+      self.uploads.lock().unwrap().get(&id),
+      Some(upload) if upload.post_id == post_id && upload.expires_at > Utc::now() && !upload.used
+    ))
+  }
+
+  async fn mark_used(&self, post_id: Uuid, id: Uuid) -> Result<bool, Error> {
+    if !self.is_pending(post_id, id).await? {
+      return Ok(false);
+    }
+    if let Some(upload) = self.uploads.lock().unwrap().get_mut(&id) {
+      upload.used = true;
+    }
+
+    Ok(true)
+  }
+}
+
+#[derive(Default)]
+pub struct MemoryCommentRepository {
+  comments: Mutex<HashMap<Uuid, Comment>>,
+}
+
+#[async_trait]
+impl CommentRepository for MemoryCommentRepository {
+  async fn get_all(&self) -> Result<Vec<Comment>, Error> {
+    Ok(self.comments.lock().unwrap().values().map(copy_comment).collect())
+  }
+
+  async fn get_all_for_post(&self, post_id: Uuid) -> Result<Vec<Comment>, Error> {
+    Ok(self.comments.lock().unwrap().values().filter(|comment| comment.post_id == post_id).map(copy_comment).collect())
+  }
+
+  async fn get_all_for_user(&self, user_id: Uuid) -> Result<Vec<Comment>, Error> {
+    Ok(self.comments.lock().unwrap().values().filter(|comment| comment.user_id == user_id).map(copy_comment).collect())
+  }
+
+  async fn get_one(&self, id: Uuid) -> Result<Comment, Error> {
+    self.comments.lock().unwrap().get(&id).map(copy_comment).ok_or_else(not_found)
+  }
+
+  async fn create(&self, mut comment: Comment) -> Result<Comment, Error> {
+    comment.id = Uuid::new_v4();
+    comment.created_at = Utc::now();
+    comment.updated_at = comment.created_at;
+    self.comments.lock().unwrap().insert(comment.id, copy_comment(&comment));
+
+    Ok(comment)
+  }
+
+  async fn update(&self, mut comment: Comment) -> Result<Comment, Error> {
+    let mut comments = self.comments.lock().unwrap();
+    let stored = comments.get(&comment.id).ok_or_else(not_found)?;
+    if stored.updated_at != comment.updated_at {
+      return Err(conflict());
+    }
+
+    comment.updated_at = next_timestamp(comment.updated_at);
+    comments.insert(comment.id, copy_comment(&comment));
+
+    Ok(comment)
+  }
+}
+
+#[derive(Default)]
+pub struct MemoryExportRepository {
+  exports: Mutex<HashMap<Uuid, Export>>,
+}
+
+impl MemoryExportRepository {
+  /// Changes a stored export directly, e.g. to let its download link expire.
+  pub fn modify(&self, id: Uuid, change: impl FnOnce(&mut Export)) {
+    change(self.exports.lock().unwrap().get_mut(&id).unwrap());
+  }
+}
+
+#[async_trait]
+impl ExportRepository for MemoryExportRepository {
+  async fn get_one(&self, id: Uuid) -> Result<Export, Error> {
+    self.exports.lock().unwrap().get(&id).cloned().ok_or_else(not_found)
+  }
+
+  async fn get_pending(&self, user_id: Uuid) -> Result<Option<Export>, Error> {
+    Ok(self.exports
+      .lock()
+      .unwrap()
+      .values()
+      .find(|export| export.user_id == user_id && export.status == ExportStatus::Pending.as_str())
+      .cloned())
+  }
+
+  async fn create(&self, user_id: Uuid) -> Result<Option<Export>, Error> {
+    if self.get_pending(user_id).await?.is_some() {
+      return Ok(None);
+    }
+
+    let now = Utc::now();
+    let export = Export {
+      id: Uuid::new_v4(),
+      user_id,
+      status: ExportStatus::Pending.as_str().to_string(),
+      object_id: None,
+      download_token: Uuid::new_v4(),
+      expires_at: None,
+      created_at: now,
+      updated_at: now,
+    };
+    self.exports.lock().unwrap().insert(export.id, export.clone());
+
+    Ok(Some(export))
+  }
+
+  async fn update(&self, mut export: Export) -> Result<Export, Error> {
+    export.updated_at = Utc::now();
+    self.exports.lock().unwrap().insert(export.id, export.clone());
+
+    Ok(export)
+  }
+
+  async fn fail_pending(&self) -> Result<u64, Error> {
+    let mut failed = 0;
+    for export in self.exports.lock().unwrap().values_mut() {
+      if export.status == ExportStatus::Pending.as_str() {
+        export.status = ExportStatus::Failed.as_str().to_string();
+        failed += 1;
+      }
+    }
+
+    Ok(failed)
+  }
+}
+
+#[derive(Default)]
+pub struct MemoryUserRepository {
+  users: Mutex<HashMap<Uuid, User>>,
+}
+
+#[async_trait]
+impl UserRepository for MemoryUserRepository {
+  async fn all(&self) -> Result<Vec<User>, Error> {
+    Ok(self.users.lock().unwrap().values().cloned().collect())
+  }
+
+  async fn get_by_id(&self, id: Uuid) -> Result<Option<User>, Error> {
+    Ok(self.users.lock().unwrap().get(&id).cloned())
+  }
+
+  async fn get_by_email(&self, email: String) -> Result<Option<User>, Error> {
+    Ok(self.users.lock().unwrap().values().find(|user| user.email == email).cloned())
+  }
+
+  async fn create(&self, user: CreateUser) -> Result<User, Error> {
+    let now = Utc::now();
+    let user = User {
+      id: Uuid::new_v4(),
+      email: user.email,
+      username: user.username,
+      password: user.password,
+      role: user.role,
+      created_at: now,
+      updated_at: now,
+    };
+    self.users.lock().unwrap().insert(user.id, user.clone());
+
+    Ok(user)
+  }
+
+  async fn update(&self, id: Uuid, user: User) -> Result<User, Error> {
+    self.users.lock().unwrap().insert(id, user.clone());
+
+    Ok(user)
+  }
+
+  async fn delete(&self, id: Uuid) -> Result<(), Error> {
+    self.users.lock().unwrap().remove(&id);
+
+    Ok(())
+  }
+}
+
+/// Deletions are recorded by the repositories, nothing is processed.
+pub struct NoopPendingDeletionService;
+
+#[async_trait]
+impl PendingDeletionService for NoopPendingDeletionService {
+  async fn process(&self) -> Result<usize, Error> {
+    Ok(0)
+  }
+
+  fn notify(&self) {}
+
+  async fn wait(&self, _timeout: Duration) {}
+}
+
+/// Post and comment services over in-memory repositories and store.
+pub struct Blog {
+  pub posts: Arc<PostServiceImpl>,
+  pub comments: Arc<CommentServiceImpl>,
+  pub post_repo: Arc<MemoryPostRepository>,
+  pub upload_repo: Arc<MemoryImageUploadRepository>,
+  pub store: Arc<MemoryStoreServiceImpl>,
+}
+
+impl Blog {
+  pub fn new() -> Self {
+    let metrics = Arc::new(init_metrics());
+    let post_repo = Arc::new(MemoryPostRepository::default());
+    let upload_repo = Arc::new(MemoryImageUploadRepository::default());
+    let store = Arc::new(MemoryStoreServiceImpl::new());
+
+    let posts = Arc::new(PostServiceImpl::new(
+      post_repo.clone(),
+      Arc::new(MemoryPostMediaRepository::default()),
+      upload_repo.clone(),
+      store.clone(),
+      Arc::new(NoopPendingDeletionService),
+      metrics.clone(),
+    ));
+    let comments = Arc::new(CommentServiceImpl::new(Arc::new(MemoryCommentRepository::default()), metrics));
+
+    Blog { posts, comments, post_repo, upload_repo, store }
+  }
+}